@@ -1,20 +1,90 @@
-use std::time::UNIX_EPOCH;
-
-use crate::jira::{Issue, Jira, Sprint};
-use crate::tui::{self, Color, CommonWidget, Terminal, Widget};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::iter;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use crate::filter::Query;
+use crate::jira::{
+    AgileBoard, Assignable, BoardColumn, ChangeAuthors, Comment, Description, Issue, IssueChange,
+    IssueType, IssueUpdate, Jira, JiraError, NewIssue, Project, Rank, SavedQuery, Sprint,
+    SprintUpdate, Status, Transition, User,
+};
+use crate::tui::{
+    self, Attributes, Color, CommonWidget, Scroll, Span, Style, Terminal, Vector2, Widget,
+};
+use crate::{editor, markup, unicode};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Deserialize, Serialize)]
+/// How many requests run at the same time when acting on every marked issue.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+/// How long the rows of issues changed by a sync stand out.
+const CHANGE_HIGHLIGHT: Duration = Duration::from_secs(60);
+
+/// Where the issues of an entry in the Sprints pane come from.
+#[derive(Clone, Deserialize, Serialize)]
+pub enum IssueSource {
+    /// The backlog is a pseudo-sprint with the id 0
+    Sprint(Sprint),
+    Query(SavedQuery),
+}
+
+impl IssueSource {
+    pub fn name(&self) -> &str {
+        match self {
+            IssueSource::Sprint(sprint) => &sprint.name,
+            IssueSource::Query(query) => &query.name,
+        }
+    }
+
+    pub fn sprint(&self) -> Option<&Sprint> {
+        match self {
+            IssueSource::Sprint(sprint) => Some(sprint),
+            IssueSource::Query(_) => None,
+        }
+    }
+
+    /// Whether both are the same source, even if some of their details changed.
+    fn is(&self, other: &IssueSource) -> bool {
+        match (self, other) {
+            (IssueSource::Sprint(sprint), IssueSource::Sprint(other)) => sprint.id == other.id,
+            (IssueSource::Query(query), IssueSource::Query(other)) => query.name == other.name,
+            _ => false,
+        }
+    }
+}
+
+/// The issues of every source, in the same order as the sources.
+#[derive(Clone, Default, Deserialize, Serialize)]
 pub struct State {
-    pub sprints: Vec<Sprint>,
+    pub sources: Vec<IssueSource>,
     pub issues: Vec<Vec<Issue>>,
+    #[serde(default)]
+    pub columns: Vec<BoardColumn>,
+    /// What went wrong fetching the saved queries, they don't stop the rest from syncing
+    #[serde(skip)]
+    pub errors: Vec<String>,
+    /// Names of the saved queries that could not be fetched, they keep their last issues
+    #[serde(skip)]
+    pub stale_queries: Vec<String>,
+    /// Who made the changes merged since the last sync, by issue id
+    #[serde(skip)]
+    pub authors: HashMap<String, ChangeAuthors>,
 }
 
 impl State {
-    pub fn new(jira: &Jira, board_id: &str) -> State {
+    /// The sprints of the board go first, then the backlog, the `queries` from the config and the
+    /// favourite filters of the user. Saved queries are fetched on a best-effort basis, see
+    /// `errors`.
+    pub fn new(jira: &Jira, board_id: &str, queries: &[SavedQuery]) -> Result<State, JiraError> {
         std::thread::scope(|scope| {
             let backlog = scope.spawn(|| jira.get_backlog_issues(board_id));
-            let mut sprints = jira.get_board_active_and_future_sprints(board_id);
+            let columns = scope.spawn(|| jira.get_board_columns(board_id));
+            let favourites = scope.spawn(|| jira.get_favourite_filters());
+            let mut sprints = jira.get_board_active_and_future_sprints(board_id)?;
 
             let mut handles = Vec::with_capacity(sprints.len());
 
@@ -27,118 +97,597 @@ impl State {
             sprints.push(Sprint {
                 id: 0,
                 name: "Backlog".into(),
+                ..Default::default()
             });
 
             handles.push(backlog);
 
-            let issues = handles
+            let mut errors = Vec::new();
+            let mut queries = queries.to_vec();
+            match favourites.join().unwrap() {
+                Ok(favourites) => queries.extend(favourites),
+                Err(err) => errors.push(format!("Could not fetch the favourite filters: {err}")),
+            }
+
+            let query_handles: Vec<_> = (queries.iter())
+                .map(|query| {
+                    let jql = query.jql.clone();
+                    scope.spawn(move || jira.search_issues(&jql))
+                })
+                .collect();
+
+            let mut issues = handles
                 .into_iter()
                 .map(|handle| handle.join().unwrap())
-                .collect();
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut stale_queries = Vec::new();
+            for (query, handle) in queries.iter().zip(query_handles) {
+                match handle.join().unwrap() {
+                    Ok(results) => issues.push(results.issues),
+                    Err(err) => {
+                        errors.push(format!(
+                            "Could not fetch the issues of {}: {err}",
+                            query.name
+                        ));
+                        stale_queries.push(query.name.clone());
+                        issues.push(Vec::new());
+                    }
+                }
+            }
+
+            let columns = columns.join().unwrap()?;
 
-            State { sprints, issues }
+            let sprints = sprints.into_iter().map(IssueSource::Sprint);
+            let queries = queries.into_iter().map(IssueSource::Query);
+
+            Ok(State {
+                sources: sprints.chain(queries).collect(),
+                issues,
+                columns,
+                errors,
+                stale_queries,
+                authors: HashMap::new(),
+            })
         })
     }
+
+    /// Applies the issues that changed since the last sync. Issues keep their place unless they
+    /// moved to another sprint, then they go last in it. Saved queries are only refreshed by full
+    /// syncs, so their issues are just updated.
+    fn merge(&mut self, changes: Vec<IssueChange>) {
+        for IssueChange {
+            issue,
+            sprint_id,
+            authors,
+        } in changes
+        {
+            self.authors.insert(issue.id.clone(), authors);
+
+            // Issues without a sprint might be done in a closed one, only the ones already in the
+            // backlog are known to be there. The full sync takes care of the rest.
+            let sprint_id = match sprint_id {
+                Some(sprint_id) => sprint_id,
+                None => {
+                    let backlog = self
+                        .sprint_position(0)
+                        .and_then(|index| self.issues.get(index));
+                    if !backlog
+                        .is_some_and(|issues| issues.iter().any(|other| other.id == issue.id))
+                    {
+                        continue;
+                    }
+
+                    0
+                }
+            };
+
+            let mut previous = None;
+
+            for (index, issues) in self.issues.iter_mut().enumerate() {
+                let Some(position) = issues.iter().position(|other| other.id == issue.id) else {
+                    continue;
+                };
+
+                match self.sources.get(index) {
+                    Some(IssueSource::Sprint(_)) => {
+                        issues.remove(position);
+                        previous = Some((index, position));
+                    }
+                    _ => issues[position] = issue.clone(),
+                }
+            }
+
+            // Issues of closed sprints are not part of the state
+            let Some(index) = self.sprint_position(sprint_id) else {
+                continue;
+            };
+            let Some(issues) = self.issues.get_mut(index) else {
+                continue;
+            };
+
+            let position = match previous {
+                Some((previous, position)) if previous == index => position,
+                _ => issues.len(),
+            };
+            issues.insert(position, issue);
+        }
+    }
+
+    /// The sprints and the backlog, without the saved queries.
+    pub fn sprints(&self) -> impl Iterator<Item = &Sprint> {
+        self.sources.iter().filter_map(IssueSource::sprint)
+    }
+
+    /// The source at `index`, if it's a sprint.
+    pub fn sprint(&self, index: usize) -> Option<&Sprint> {
+        self.sources.get(index).and_then(IssueSource::sprint)
+    }
+
+    /// The index of the sprint with `sprint_id` among the sources.
+    pub fn sprint_position(&self, sprint_id: u32) -> Option<usize> {
+        (self.sources.iter())
+            .position(|source| source.sprint().is_some_and(|sprint| sprint.id == sprint_id))
+    }
+}
+
+pub enum LogLevel {
+    Info,
+    Error,
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogLevel::Info => write!(f, "INFO"),
+            LogLevel::Error => write!(f, "ERROR"),
+        }
+    }
 }
 
 pub struct App {
     terminal: Terminal,
+    jira: Arc<Jira>,
+    /// The board shown, the state and its cache belong to it
+    board_id: Box<str>,
+    /// The boards from the config, offered first in the board picker
+    board_ids: Vec<Box<str>>,
+    /// Asks the background sync for a full sync of a board, which it follows from then on
+    board_sync: mpsc::Sender<Box<str>>,
 
     pub active_window: Window,
     sprint_offset: usize,
     active_sprint: usize,
     issue_offset: usize,
     active_issue: usize,
+    sprint_filter: ListFilter,
+    issue_filter: ListFilter,
 
     state: State,
 
-    sprints: tui::ItemList,
+    sprints: tui::Table,
     issues: tui::Table,
     issue_description: tui::Text,
+    comments: tui::Text,
     logs: tui::ItemList,
+    popup: Option<Popup>,
+    prompt: Option<Prompt>,
+    /// Only laid out while the board is shown, it takes the place of the other panes
+    board: Option<Board>,
+    /// While there is one, the Issues pane shows its results instead of the active sprint
+    search: Option<Search>,
+    /// Ids of the issues picked with space, actions on them apply to all of them at once
+    marked_issues: HashSet<String>,
+    /// Ids of the issues the last syncs changed, with when they did
+    changed_issues: HashMap<String, Instant>,
+
+    /// The user behind the configured credentials, only fetched once it's needed
+    myself: Option<User>,
+
+    /// Comments are only fetched on demand, so we keep the ones from the last issue we looked at
+    issue_comments: Option<(String, Vec<Comment>)>,
 }
 
-impl App {
-    pub fn new(terminal: Terminal, initial_state: State) -> App {
-        let rendering_region = terminal.rendering_region();
+/// A floating list the user picks one entry from, what happens next depends on its kind.
+struct Popup {
+    kind: PopupKind,
+    title: String,
+    entries: Vec<String>,
+    offset: usize,
+    active: usize,
+    list: tui::ItemList,
+    /// The window that gets focused back once the popup is closed
+    parent: Window,
+}
 
-        let (top, mut logs) = rendering_region.split_horizontally_percentage(0.9);
+/// A floating text input, what happens with the text depends on its kind.
+struct Prompt {
+    kind: PromptKind,
+    title: String,
+    input: tui::Input,
+    /// The window that gets focused back once the prompt is closed
+    parent: Window,
+}
 
-        let (left, mut issue_description) = top.split_vertically_at_percentage(0.40);
-        let (mut sprints, mut issues) = left.split_horizontally_percentage(0.2);
+/// The results of a JQL query, they are not synced with the rest of the state.
+struct Search {
+    jql: String,
+    issues: Vec<Issue>,
+    /// How many issues match, only the first ones are fetched
+    total: usize,
+}
 
-        sprints.set_title(Some("[ 1 ] Sprints ".into()));
-        sprints.set_border(Some(Color::Default));
-        let sprints = sprints.item_list();
+/// The issues of the active sprint laid out as cards under the columns of the board.
+struct Board {
+    columns: Vec<tui::ItemList>,
+    /// The issues (indexes in the sprint) under every column, filtered out issues are left out
+    cards: Vec<Vec<usize>>,
+    offsets: Vec<usize>,
+    active_column: usize,
+}
 
-        issues.set_title(Some("[ 2 ] Issues ".into()));
-        issues.set_border(Some(Color::Default));
-        let issues = issues.table();
+/// The rows of a list that match its query, the offset and selection of a list refer to them.
+#[derive(Default)]
+struct ListFilter {
+    query: String,
+    /// Indexes of the items shown
+    rows: Vec<usize>,
+}
 
-        issue_description.set_title(Some("[ 3 ] Description ".into()));
-        issue_description.set_border(Some(Color::Default));
-        let issue_description = issue_description.text();
+impl ListFilter {
+    fn row(&self, index: usize) -> Option<usize> {
+        self.rows.iter().position(|&row| row == index)
+    }
 
-        logs.set_title(Some("Logs".into()));
-        logs.set_border(Some(Color::Default));
-        let logs = logs.item_list();
+    fn is_active(&self) -> bool {
+        !self.query.trim().is_empty()
+    }
+}
+
+enum PromptKind {
+    Comment {
+        issue_id: String,
+        issue_name: String,
+    },
+    Label {
+        issues: Vec<(String, String)>,
+    },
+    IssueFilter,
+    SprintFilter,
+    Search,
+    Board,
+    IssueSummary {
+        sprint_id: u32,
+        issue: NewIssue,
+    },
+    IssueDescription {
+        sprint_id: u32,
+        issue: NewIssue,
+    },
+}
+
+enum PopupKind {
+    Transitions {
+        issue_id: String,
+        issue_name: String,
+        transitions: Vec<Transition>,
+    },
+    IssueProject {
+        sprint_id: u32,
+        projects: Vec<Project>,
+    },
+    IssueType {
+        sprint_id: u32,
+        issue: NewIssue,
+        issue_types: Vec<IssueType>,
+    },
+    /// Every marked issue (id and name) with the transitions it has, the entries are the
+    /// statuses at least one of them can be moved to
+    BulkTransitions {
+        issues: Vec<(String, String, Vec<Transition>)>,
+        statuses: Vec<Status>,
+    },
+    MoveIssues {
+        issues: Vec<(String, String)>,
+        sprints: Vec<Sprint>,
+    },
+    /// The entries come from `assignee_entries`
+    Assignee {
+        issues: Vec<(String, String)>,
+        users: Vec<User>,
+    },
+    /// The first entry cancels, so sprints are not started by mistake
+    StartSprint {
+        sprint: Sprint,
+        update: SprintUpdate,
+    },
+    /// The first entry cancels, as closed sprints can't be reopened. The unfinished issues (id
+    /// and name) are moved to the `targets` of the next entries before the sprint is completed,
+    /// without them the only other entry completes it
+    CompleteSprint {
+        sprint: Sprint,
+        issues: Vec<(String, String)>,
+        targets: Vec<Sprint>,
+    },
+    /// The first entry cancels, so nothing is deleted by mistake
+    Delete {
+        issues: Vec<(String, String)>,
+    },
+    Board {
+        boards: Vec<AgileBoard>,
+    },
+    /// The entries come from `assignee_entries`
+    IssueAssignee {
+        sprint_id: u32,
+        issue: NewIssue,
+        users: Vec<User>,
+    },
+}
 
+impl App {
+    pub fn new(
+        terminal: Terminal,
+        jira: Arc<Jira>,
+        board_ids: Vec<Box<str>>,
+        board_sync: mpsc::Sender<Box<str>>,
+        initial_state: State,
+    ) -> App {
         let mut ui = App {
             terminal,
+            jira,
+            board_id: board_ids[0].clone(),
+            board_ids,
+            board_sync,
             active_sprint: 0,
             sprint_offset: 0,
             active_issue: 0,
             issue_offset: 0,
+            sprint_filter: ListFilter::default(),
+            issue_filter: ListFilter::default(),
             state: initial_state,
             active_window: Window::Sprints,
-            sprints,
-            issues,
-            issue_description,
-            logs,
+            sprints: tui::Table::default(),
+            issues: tui::Table::default(),
+            issue_description: tui::Text::default(),
+            comments: tui::Text::default(),
+            logs: tui::ItemList::default(),
+            popup: None,
+            prompt: None,
+            board: None,
+            search: None,
+            marked_issues: HashSet::new(),
+            changed_issues: HashMap::new(),
+            myself: None,
+            issue_comments: None,
         };
 
+        ui.layout();
+
         // We need to do the initial sync to show the data into the terminal
         ui.sync_state();
-        ui.sprints.set_selected(Some(ui.active_sprint));
-        ui.sprints.set_border(Some(Color::Green));
+        ui.highlight_active_window();
 
         ui
     }
 
-    pub fn load_state() -> Option<State> {
+    /// Splits the screen between the widgets, it's redone every time the terminal is resized.
+    fn layout(&mut self) {
+        let rendering_region = self.terminal.rendering_region();
+
+        let (top, mut logs) = rendering_region.split_horizontally_percentage(0.9);
+
+        let (left, right) = top.split_vertically_at_percentage(0.40);
+        let (mut sprints, mut issues) = left.split_horizontally_percentage(0.2);
+        let (mut issue_description, mut comments) = right.split_horizontally_percentage(0.6);
+
+        sprints.set_title(Some("[ 1 ] Sprints ".into()));
+        sprints.set_border(Some(Color::Default));
+        self.sprints = sprints.table();
+
+        issues.set_title(Some("[ 2 ] Issues ".into()));
+        issues.set_border(Some(Color::Default));
+        self.issues = issues.table();
+
+        issue_description.set_title(Some("[ 3 ] Description ".into()));
+        issue_description.set_border(Some(Color::Default));
+        self.issue_description = issue_description.text();
+
+        comments.set_title(Some("[ 4 ] Comments ".into()));
+        comments.set_border(Some(Color::Default));
+        self.comments = comments.text();
+
+        logs.set_title(Some("Logs".into()));
+        logs.set_border(Some(Color::Default));
+        let mut logs = logs.item_list();
+
+        // We keep the most recent logs that still fit
+        let mut log_items = std::mem::take(self.logs.get_items_mut());
+        let overflow = log_items.len().saturating_sub(logs.usable_size().height);
+        log_items.drain(..overflow);
+        *logs.get_items_mut() = log_items;
+        self.logs = logs;
+
+        if self.board.is_some() {
+            let columns = self.board_columns();
+            if let Some(board) = &mut self.board {
+                board.columns = columns;
+            }
+        }
+
+        if let Some(mut popup) = self.popup.take() {
+            popup.list = self.popup_region(&popup.title, &popup.entries).item_list();
+            popup.offset =
+                scroll_offset(popup.offset, popup.active, popup.list.usable_size().height);
+            popup.sync();
+            self.popup = Some(popup);
+        }
+
+        if let Some(mut prompt) = self.prompt.take() {
+            let mut input = self.prompt_region(&prompt.title, &prompt.kind).input();
+            prompt.input.text().chars().for_each(|c| input.push(c));
+            prompt.input = input;
+            self.prompt = Some(prompt);
+        }
+    }
+
+    /// Reads the new terminal size and lays out every widget again, keeping the selections.
+    pub fn resize(&mut self) {
+        if let Err(err) = self.terminal.resize() {
+            self.log(
+                LogLevel::Error,
+                format!("Could not resize the terminal: {err}"),
+            );
+            return;
+        }
+
+        self.layout();
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Applies the issues of the board that changed since the last sync, see [`State::merge`].
+    pub fn update_issues(&mut self, board_id: &str, changes: Vec<IssueChange>) {
+        if *self.board_id != *board_id || changes.is_empty() {
+            return;
+        }
+
+        if let Some(search) = &mut self.search {
+            for change in &changes {
+                let issue = &change.issue;
+                if let Some(other) = search.issues.iter_mut().find(|other| other.id == issue.id) {
+                    *other = issue.clone();
+                }
+            }
+        }
+
+        let mut state = self.state.clone();
+        state.merge(changes);
+        self.update_state(board_id, state);
+    }
+
+    /// Drops every change the background sync could have missed, like deleted issues or new
+    /// sprints, by fetching the whole board again.
+    pub fn sync_board(&mut self) {
+        match self.board_sync.send(self.board_id.clone()) {
+            Ok(()) => self.log(LogLevel::Info, "Syncing the whole board"),
+            Err(_) => self.log(LogLevel::Error, "The background sync is not running"),
+        }
+    }
+
+    /// Every board has its own cache, so switching boards doesn't lose the others.
+    fn state_path(board_id: &str) -> String {
         let home_directory = std::env::var("HOME").unwrap();
-        let Ok(file) = std::fs::File::open(format!("{home_directory}/.canoa.json")) else {
-            return None;
-        };
-        let file = std::io::BufReader::new(file);
-        let state = serde_json::from_reader(file).unwrap();
-        Some(state)
+        format!("{home_directory}/.canoa.{board_id}.json")
     }
 
-    pub fn save_state(&self) {
+    pub fn load_state(board_id: &str) -> Option<State> {
+        read_cache(&App::state_path(board_id))
+    }
+
+    /// The cache from before every board had its own, it belongs to the first configured board.
+    pub fn load_legacy_state() -> Option<State> {
+        #[derive(Deserialize)]
+        struct LegacyState {
+            sprints: Vec<Sprint>,
+            issues: Vec<Vec<Issue>>,
+            #[serde(default)]
+            columns: Vec<BoardColumn>,
+        }
+
         let home_directory = std::env::var("HOME").unwrap();
-        let file = std::fs::File::create(format!("{home_directory}/.canoa.json")).unwrap();
+        let state: LegacyState = read_cache(&format!("{home_directory}/.canoa.json"))?;
+
+        Some(State {
+            sources: state.sprints.into_iter().map(IssueSource::Sprint).collect(),
+            issues: state.issues,
+            columns: state.columns,
+            ..Default::default()
+        })
+    }
+
+    pub fn save_state(&self) {
+        let file = std::fs::File::create(App::state_path(&self.board_id)).unwrap();
         let file = std::io::BufWriter::new(file);
         serde_json::to_writer(file, &self.state).unwrap();
     }
 
-    pub fn update_state(&mut self, state: State) {
-        let current_sprint_id = self.state.sprints[self.active_sprint].id;
-        let current_issue_id = &self.state.issues[self.active_sprint][self.active_issue].id;
+    /// Logs and highlights what changed on every issue.
+    pub fn update_state(&mut self, board_id: &str, mut state: State) {
+        // A sync that started before the board was switched
+        if *self.board_id != *board_id {
+            return;
+        }
+
+        // The saved queries that failed show what they had until they work again
+        for name in std::mem::take(&mut state.stale_queries) {
+            let is_query = |source: &IssueSource| match source {
+                IssueSource::Query(query) => query.name == name,
+                IssueSource::Sprint(_) => false,
+            };
+            let old = self.state.sources.iter().position(is_query);
+            let new = state.sources.iter().position(is_query);
+
+            if let (Some(old), Some(new)) = (old, new)
+                && let (Some(old_issues), Some(new_issues)) =
+                    (self.state.issues.get(old), state.issues.get_mut(new))
+            {
+                new_issues.clone_from(old_issues);
+            }
+        }
+
+        for error in std::mem::take(&mut state.errors) {
+            self.log(LogLevel::Error, error);
+        }
+
+        let authors = std::mem::take(&mut state.authors);
+        let changes = state_changes(&self.state, &state, &authors);
+
+        let current_source = self.state.sources.get(self.active_sprint);
+        let current_issue_id = self.selected_issue().map(|issue| issue.id.clone());
 
         self.active_sprint = state
-            .sprints
+            .sources
             .iter()
-            .position(|sprint| sprint.id == current_sprint_id)
+            .position(|source| current_source.is_some_and(|current| current.is(source)))
             .unwrap_or(0);
 
-        self.active_issue = state.issues[self.sprint_offset]
-            .iter()
-            .position(|issue| &issue.id == current_issue_id)
-            .unwrap_or(0);
+        // The search results are not part of the state, so they keep their selection
+        if self.search.is_none() {
+            self.active_issue = state
+                .issues
+                .get(self.active_sprint)
+                .and_then(|issues| {
+                    issues
+                        .iter()
+                        .position(|issue| Some(&issue.id) == current_issue_id.as_ref())
+                })
+                .unwrap_or(0);
+        }
 
         self.state = state;
 
+        // Issues that are gone can't be acted on anymore
+        let ids = self
+            .all_issues()
+            .map(|issue| issue.id.clone())
+            .collect::<HashSet<_>>();
+        self.marked_issues.retain(|id| ids.contains(id));
+
+        self.log(LogLevel::Info, "Synced state");
+
+        let now = Instant::now();
+        for (issue_id, change) in changes {
+            self.log(LogLevel::Info, change);
+            self.changed_issues.insert(issue_id, now);
+        }
+        self.changed_issues
+            .retain(|_, changed| changed.elapsed() < CHANGE_HIGHLIGHT);
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    pub fn log(&mut self, level: LogLevel, message: impl Display) {
         let time_elapsed_since_unix_epoch = std::time::SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -152,33 +701,131 @@ impl App {
 
         let logs_max_count = self.logs.usable_size().height;
         let log_items = self.logs.get_items_mut();
-        if log_items.len() >= logs_max_count {
-            log_items.swap_remove(0);
-        }
         log_items.push(format!(
-            "{hours:0>2}:{minutes:0>2}:{seconds:0>2} INFO: Synced state"
+            "{hours:0>2}:{minutes:0>2}:{seconds:0>2} {level}: {message}"
         ));
+        let overflow = log_items.len().saturating_sub(logs_max_count);
+        log_items.drain(..overflow);
+    }
 
-        self.sync_state();
+    /// The issues of the active sprint, which might not exist yet before the first sync.
+    fn sprint_issues(&self) -> &[Issue] {
+        self.state
+            .issues
+            .get(self.active_sprint)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
 
-        match self.active_window {
-            Window::Description => (),
-            Window::Issues => self.issues.set_selected(Some(self.active_issue)),
-            Window::Sprints => self.sprints.set_selected(Some(self.active_sprint)),
+    /// The issues in the Issues pane, the search results take the place of the active sprint.
+    fn shown_issues(&self) -> &[Issue] {
+        match &self.search {
+            Some(search) => &search.issues,
+            None => self.sprint_issues(),
         }
     }
 
+    /// Every issue we know of, an issue can be both in a sprint and in the search results.
+    fn all_issues(&self) -> impl Iterator<Item = &Issue> {
+        let search = self.search.iter().flat_map(|search| &search.issues);
+        self.state.issues.iter().flatten().chain(search)
+    }
+
+    fn all_issues_mut(&mut self) -> impl Iterator<Item = &mut Issue> {
+        let search = self.search.iter_mut().flat_map(|search| &mut search.issues);
+        self.state.issues.iter_mut().flatten().chain(search)
+    }
+
+    /// Filtered out issues can't be selected.
+    fn selected_issue(&self) -> Option<&Issue> {
+        self.issue_filter.row(self.active_issue)?;
+        self.shown_issues().get(self.active_issue)
+    }
+
+    /// The sprints go first, filtering them might change the active sprint.
     pub fn sync_state(&mut self) {
+        self.sync_sprints_window();
         self.sync_issues_window();
         self.sync_issue_description_window();
-        self.sync_sprints_window();
+        self.sync_comments_window();
+    }
+
+    /// Finds the sprints that match the filter, keeping the active one among them.
+    fn filter_sprints(&mut self) {
+        let query = Query::parse(&self.sprint_filter.query);
+
+        self.sprint_filter.rows = (self.state.sources.iter().enumerate())
+            .filter(|(_, source)| query.matches_name(source.name()))
+            .map(|(index, _)| index)
+            .collect();
+
+        if self.sprint_filter.row(self.active_sprint).is_none()
+            && let Some(&first) = self.sprint_filter.rows.first()
+        {
+            self.active_sprint = first;
+
+            // The search results don't depend on the sprint
+            if self.search.is_none() {
+                self.active_issue = 0;
+                self.issue_offset = 0;
+            }
+        }
+
+        let row = self.sprint_filter.row(self.active_sprint).unwrap_or(0);
+        self.sprint_offset =
+            scroll_offset(self.sprint_offset, row, self.sprints.usable_size().height);
+    }
+
+    /// Finds the issues of the active sprint that match the filter, keeping the active one
+    /// among them.
+    fn filter_issues(&mut self) {
+        let query = Query::parse(&self.issue_filter.query);
+        // It's resolved when the prompt opens, so there are no requests while typing or syncing
+        let myself = match query.mentions_me() {
+            true => self.myself.as_ref().map(|user| user.account_id.clone()),
+            false => None,
+        };
+
+        self.issue_filter.rows = (self.shown_issues().iter().enumerate())
+            .filter(|(_, issue)| query.matches_issue(issue, myself.as_deref()))
+            .map(|(index, _)| index)
+            .collect();
+
+        if self.issue_filter.row(self.active_issue).is_none()
+            && let Some(&first) = self.issue_filter.rows.first()
+        {
+            self.active_issue = first;
+        }
+
+        let row = self.issue_filter.row(self.active_issue).unwrap_or(0);
+        self.issue_offset = scroll_offset(self.issue_offset, row, self.issues.usable_size().height);
     }
 
     pub fn sync_issues_window(&mut self) {
-        let issues_table = self.state.issues[self.active_sprint][self.issue_offset..]
-            .iter()
-            .take(self.issues.usable_size().height)
-            .map(|issue| {
+        self.filter_issues();
+
+        let issues = self.shown_issues();
+        let visible_rows = (self.issue_filter.rows.iter())
+            .skip(self.issue_offset)
+            .take(self.issues.usable_size().height);
+
+        let marked_rows = (visible_rows.clone().enumerate())
+            .filter(|(_, index)| self.marked_issues.contains(&issues[**index].id))
+            .map(|(row, _)| row)
+            .collect();
+
+        let changed_rows = (visible_rows.clone().enumerate())
+            .filter(|(_, index)| {
+                (self.changed_issues.get(&issues[**index].id))
+                    .is_some_and(|changed| changed.elapsed() < CHANGE_HIGHLIGHT)
+            })
+            .map(|(row, _)| row)
+            .collect();
+
+        let issues_table = visible_rows
+            .map(|&index| {
+                let issue = &issues[index];
+
                 vec![
                     issue.name.clone(),
                     issue.fields.status.clone(),
@@ -186,9 +833,10 @@ impl App {
                     issue
                         .fields
                         .assignee
-                        .clone()
+                        .as_ref()
                         .map(|assignee| {
                             assignee
+                                .display_name
                                 .split(" ")
                                 .flat_map(|s| s.chars().nth(0))
                                 .take(3)
@@ -200,155 +848,2732 @@ impl App {
             })
             .collect();
 
+        let name = match &self.search {
+            Some(search) if search.total > search.issues.len() => format!(
+                "{} (showing {} of {})",
+                search.jql,
+                search.issues.len(),
+                search.total
+            ),
+            Some(search) => search.jql.clone(),
+            None => "Issues".into(),
+        };
+
+        let mut title = match self.issue_filter.is_active() {
+            true => format!(
+                "[ 2 ] {name} ({}/{}) ",
+                self.issue_filter.rows.len(),
+                issues.len()
+            ),
+            false => format!("[ 2 ] {name} "),
+        };
+
+        if !self.marked_issues.is_empty() {
+            title.push_str(&format!("[ {} marked ] ", self.marked_issues.len()));
+        }
+
         self.issues.change_table(issues_table);
+        self.issues.set_marked(marked_rows);
+        self.issues.set_changed(changed_rows);
+        self.issues.set_title(Some(title));
+
+        self.sync_board_window();
+    }
+
+    /// Groups the visible issues by column, the focus follows the active issue.
+    fn sync_board_window(&mut self) {
+        let Some(mut board) = self.board.take() else {
+            return;
+        };
+
+        // A sync might bring a different set of columns
+        if board.columns.len() != self.state.columns.len() {
+            board.columns = self.board_columns();
+            board.offsets = vec![0; board.columns.len()];
+        }
+
+        // Borrowing the fields instead of `shown_issues` lets us change the active issue
+        let issues = match &self.search {
+            Some(search) => search.issues.as_slice(),
+            None => (self.state.issues.get(self.active_sprint))
+                .map(Vec::as_slice)
+                .unwrap_or_default(),
+        };
+        board.cards = (self.state.columns.iter())
+            .map(|column| {
+                (self.issue_filter.rows.iter().copied())
+                    .filter(|&index| column.statuses.contains(&issues[index].fields.status_id))
+                    .collect()
+            })
+            .collect();
+
+        let active = (board.cards.iter().enumerate()).find_map(|(column, cards)| {
+            let row = cards.iter().position(|&index| index == self.active_issue)?;
+            Some((column, row))
+        });
+
+        // The active issue might not be on the board, e.g. its status has no column
+        let (column, row) = match active {
+            Some(active) => active,
+            None => {
+                let column = (board.cards.iter())
+                    .position(|cards| !cards.is_empty())
+                    .unwrap_or(0);
+
+                if let Some(&index) = board.cards.get(column).and_then(|cards| cards.first()) {
+                    self.active_issue = index;
+                }
+
+                (column, 0)
+            }
+        };
+
+        board.active_column = column;
+        if let Some(list) = board.columns.get(column) {
+            board.offsets[column] =
+                scroll_offset(board.offsets[column], row, list.usable_size().height);
+        }
+
+        for (column, list) in board.columns.iter_mut().enumerate() {
+            let cards = (board.cards[column].iter())
+                .skip(board.offsets[column])
+                .take(list.usable_size().height)
+                .map(|&index| format!("{} {}", issues[index].name, issues[index].fields.summary))
+                .collect();
+
+            list.change_list(cards);
+            list.set_title(Some(format!(
+                " {} ({}) ",
+                self.state.columns[column].name,
+                board.cards[column].len()
+            )));
+        }
+
+        self.board = Some(board);
+
+        if active.is_none() {
+            self.sync_issue_description_window();
+            self.sync_comments_window();
+        }
+
+        self.highlight_active_window();
     }
 
     pub fn sync_issue_description_window(&mut self) {
-        self.issue_description.set_text(
-            self.state.issues[self.active_sprint][self.active_issue]
-                .fields
-                .description
-                .clone(),
-        );
+        let spans = self
+            .selected_issue()
+            .and_then(|issue| issue.fields.description.as_ref())
+            .map(|description| description.document().spans())
+            .unwrap_or_default();
+
+        self.issue_description.set_spans(spans);
+    }
+
+    pub fn sync_comments_window(&mut self) {
+        let selected_issue_id = self.selected_issue().map(|issue| issue.id.as_str());
+
+        let spans = match &self.issue_comments {
+            Some((issue_id, comments)) if Some(issue_id.as_str()) == selected_issue_id => {
+                if comments.is_empty() {
+                    vec![Span::new(
+                        "No comments yet, press 'c' to write one",
+                        Style::default(),
+                    )]
+                } else {
+                    let header_style = Style {
+                        foreground_color: Some(Color::Cyan),
+                        attributes: Attributes {
+                            bold: true,
+                            ..Default::default()
+                        },
+                    };
+
+                    let mut spans = Vec::new();
+
+                    for comment in comments {
+                        let header = format!(
+                            "{} · {}\n",
+                            comment.author.as_deref().unwrap_or("Anonymous"),
+                            // '2024-05-01T12:34:56.000+0000' becomes '2024-05-01 12:34'
+                            comment
+                                .created
+                                .get(..16)
+                                .unwrap_or(&comment.created)
+                                .replace('T', " "),
+                        );
+
+                        spans.push(Span::new(header, header_style));
+                        spans.extend(markup::parse_wiki(&comment.body).spans());
+                        spans.push(Span::new("\n", Style::default()));
+                    }
+
+                    spans
+                }
+            }
+            _ if selected_issue_id.is_some() => {
+                vec![Span::new("Press 4 to load the comments", Style::default())]
+            }
+            _ => Vec::new(),
+        };
+
+        self.comments.set_spans(spans);
     }
 
     pub fn sync_sprints_window(&mut self) {
-        let sprints_list = self.state.sprints[self.sprint_offset..]
+        self.filter_sprints();
+
+        // Only the day is shown, e.g. '2024-05-01T09:00:00.000Z' becomes '2024-05-01'
+        let day = |date: &Option<String>| match date {
+            Some(date) => date.get(..10).unwrap_or(date).to_string(),
+            None => "?".into(),
+        };
+
+        let sprints_table = self
+            .sprint_filter
+            .rows
             .iter()
+            .skip(self.sprint_offset)
             .take(self.sprints.usable_size().height)
-            .map(|sprint| sprint.name.clone())
+            .map(|&index| {
+                let sprint = match &self.state.sources[index] {
+                    IssueSource::Sprint(sprint) => sprint,
+                    IssueSource::Query(query) => {
+                        return vec![
+                            query.name.clone(),
+                            "query".into(),
+                            String::new(),
+                            query.jql.clone(),
+                        ];
+                    }
+                };
+
+                let dates = match sprint.start_date.is_some() || sprint.end_date.is_some() {
+                    true => format!("{} → {}", day(&sprint.start_date), day(&sprint.end_date)),
+                    false => String::new(),
+                };
+                let goal = sprint.goal.as_deref().unwrap_or_default();
+
+                vec![
+                    sprint.name.clone(),
+                    sprint.state.clone(),
+                    dates,
+                    goal.lines().next().unwrap_or_default().to_string(),
+                ]
+            })
             .collect();
 
-        self.sprints.change_list(sprints_list);
+        let title = match self.sprint_filter.is_active() {
+            true => format!(
+                "[ 1 ] Sprints ({}/{}) ",
+                self.sprint_filter.rows.len(),
+                self.state.sources.len()
+            ),
+            false => "[ 1 ] Sprints ".into(),
+        };
+
+        self.sprints.change_table(sprints_table);
+        self.sprints.set_title(Some(title));
     }
 
     pub fn render(&mut self) {
-        self.sprints.render(&mut self.terminal.buffer);
-        self.issues.render(&mut self.terminal.buffer);
-        self.issue_description.render(&mut self.terminal.buffer);
+        match &self.board {
+            Some(board) => {
+                for column in &board.columns {
+                    column.render(&mut self.terminal.buffer);
+                }
+            }
+            None => {
+                self.sprints.render(&mut self.terminal.buffer);
+                self.issues.render(&mut self.terminal.buffer);
+                self.issue_description.render(&mut self.terminal.buffer);
+                self.comments.render(&mut self.terminal.buffer);
+            }
+        }
         self.logs.render(&mut self.terminal.buffer);
 
+        if let Some(popup) = &self.popup {
+            popup.list.render(&mut self.terminal.buffer);
+        }
+
+        if let Some(prompt) = &self.prompt {
+            prompt.input.render(&mut self.terminal.buffer);
+        }
+
         self.terminal.draw();
     }
 
     pub fn select_sprints_window(&mut self) {
         self.unselect_windows();
         self.active_window = Window::Sprints;
-        self.sprints.set_border(Some(Color::Green));
-        self.sprints.set_selected(Some(self.active_sprint));
+        self.highlight_active_window();
     }
 
     pub fn select_issues_window(&mut self) {
         self.unselect_windows();
         self.active_window = Window::Issues;
-        self.issues.set_border(Some(Color::Green));
-        self.issues.set_selected(Some(self.active_issue));
+        self.highlight_active_window();
     }
 
     pub fn select_issue_description_window(&mut self) {
         self.unselect_windows();
         self.active_window = Window::Description;
-        self.issue_description.set_border(Some(Color::Green));
+        self.highlight_active_window();
     }
 
-    fn unselect_windows(&mut self) {
-        match self.active_window {
-            Window::Sprints => {
-                self.issue_description.set_border(Some(Color::Default));
-                self.sprints.set_selected(None);
-            }
-            Window::Issues => {
-                self.issues.set_border(Some(Color::Default));
-                self.issues.set_selected(None);
-            }
-            Window::Description => self.issue_description.set_border(Some(Color::Default)),
-        };
-    }
+    /// Shows the issues of the active sprint under the columns of the board, or goes back to
+    /// the issues if it's already shown.
+    pub fn toggle_board(&mut self) {
+        if matches!(self.active_window, Window::Board) {
+            self.select_issues_window();
+            return;
+        }
 
-    pub fn move_issue_selection_down(&mut self) {
-        if self.active_issue >= self.state.issues[self.active_sprint].len() - 1 {
+        // Boards cached by older versions don't have their columns until the next sync
+        if self.state.columns.is_empty() {
+            self.log(LogLevel::Error, "The board has no columns yet");
             return;
-        };
+        }
 
-        self.active_issue += 1;
+        self.unselect_windows();
+        self.board = Some(Board {
+            columns: self.board_columns(),
+            cards: Vec::new(),
+            offsets: vec![0; self.state.columns.len()],
+            active_column: 0,
+        });
+        self.active_window = Window::Board;
+        self.sync_board_window();
+    }
 
-        if self.active_issue - self.issue_offset >= self.issues.usable_size().height {
-            self.issue_offset += 1;
-            self.sync_issues_window();
+    /// The board takes the place of every pane but the logs, its columns share the width.
+    fn board_columns(&self) -> Vec<tui::ItemList> {
+        let (mut region, _) = self
+            .terminal
+            .rendering_region()
+            .split_horizontally_percentage(0.9);
+
+        let mut columns = Vec::with_capacity(self.state.columns.len());
+        for remaining in (1..=self.state.columns.len()).rev() {
+            let width = region.size.width / remaining;
+            let (mut column, rest) = region.split_vertically_at(width as u32);
+            column.set_border(Some(Color::Default));
+            columns.push(column.item_list());
+            region = rest;
         }
 
-        self.issues
-            .set_selected(Some(self.active_issue - self.issue_offset));
+        columns
+    }
+
+    /// Going back to the issues keeps the card that was selected on the board.
+    fn close_board(&mut self) {
+        if self.board.take().is_none() {
+            return;
+        }
+
+        let row = self.issue_filter.row(self.active_issue).unwrap_or(0);
+        self.issue_offset = scroll_offset(self.issue_offset, row, self.issues.usable_size().height);
+        self.sync_issues_window();
+    }
+
+    /// Focusing the comments (re)loads them for the selected issue.
+    pub fn select_comments_window(&mut self) {
+        self.unselect_windows();
+        self.active_window = Window::Comments;
+        self.highlight_active_window();
+
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let (issue_id, issue_name) = (issue.id.clone(), issue.name.clone());
+
+        match self.jira.get_comments(&issue_name) {
+            Ok(comments) => self.issue_comments = Some((issue_id, comments)),
+            Err(err) => self.log(
+                LogLevel::Error,
+                format!("Could not fetch the comments of {issue_name}: {err}"),
+            ),
+        }
+
+        self.sync_comments_window();
+    }
+
+    /// Popups and prompts keep the window they were opened from highlighted.
+    fn highlight_active_window(&mut self) {
+        let window = match self.active_window {
+            Window::Popup => self.popup.as_ref().map(|popup| popup.parent),
+            Window::Prompt => self.prompt.as_ref().map(|prompt| prompt.parent),
+            window => Some(window),
+        };
+
+        match window {
+            Some(Window::Sprints) => {
+                let row = self.sprint_filter.row(self.active_sprint);
+                self.sprints.set_border(Some(Color::Green));
+                self.sprints
+                    .set_selected(row.and_then(|row| row.checked_sub(self.sprint_offset)));
+            }
+            Some(Window::Issues) => {
+                let row = self.issue_filter.row(self.active_issue);
+                self.issues.set_border(Some(Color::Green));
+                self.issues
+                    .set_selected(row.and_then(|row| row.checked_sub(self.issue_offset)));
+            }
+            Some(Window::Description) => self.issue_description.set_border(Some(Color::Green)),
+            Some(Window::Comments) => self.comments.set_border(Some(Color::Green)),
+            Some(Window::Board) => {
+                let Some(board) = &mut self.board else {
+                    return;
+                };
+
+                for (column, list) in board.columns.iter_mut().enumerate() {
+                    let row = (board.cards[column].iter())
+                        .position(|&index| index == self.active_issue)
+                        .filter(|_| column == board.active_column);
+
+                    list.set_border(Some(match row {
+                        Some(_) => Color::Green,
+                        None => Color::Default,
+                    }));
+                    list.set_selected(row.and_then(|row| row.checked_sub(board.offsets[column])));
+                }
+            }
+            Some(Window::Popup | Window::Prompt) | None => (),
+        }
+    }
+
+    fn unselect_windows(&mut self) {
+        match self.active_window {
+            Window::Sprints => {
+                self.sprints.set_border(Some(Color::Default));
+                self.sprints.set_selected(None);
+            }
+            Window::Issues => {
+                self.issues.set_border(Some(Color::Default));
+                self.issues.set_selected(None);
+            }
+            Window::Description => self.issue_description.set_border(Some(Color::Default)),
+            Window::Comments => self.comments.set_border(Some(Color::Default)),
+            Window::Board => self.close_board(),
+            Window::Popup | Window::Prompt => (),
+        };
+    }
+
+    pub fn move_issue_selection_down(&mut self) {
+        let row = self
+            .issue_filter
+            .row(self.active_issue)
+            .map_or(0, |row| row + 1);
+
+        if let Some(&index) = self.issue_filter.rows.get(row) {
+            self.select_issue(index);
+        }
+    }
+
+    pub fn move_issue_selection_up(&mut self) {
+        let row = self.issue_filter.row(self.active_issue);
+
+        if let Some(&index) = row
+            .and_then(|row| row.checked_sub(1))
+            .and_then(|row| self.issue_filter.rows.get(row))
+        {
+            self.select_issue(index);
+        }
+    }
+
+    /// Marks or unmarks the selected issue and moves on to the next one, like in file managers.
+    pub fn toggle_issue_mark(&mut self) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let issue_id = issue.id.clone();
+
+        if !self.marked_issues.remove(&issue_id) {
+            self.marked_issues.insert(issue_id);
+        }
+
+        self.sync_issues_window();
+        self.highlight_active_window();
+        self.move_issue_selection_down();
+    }
+
+    /// Makes the issue at `index` of the sprint the active one, scrolling it into view.
+    fn select_issue(&mut self, index: usize) {
+        let Some(row) = self.issue_filter.row(index) else {
+            return;
+        };
+
+        self.active_issue = index;
+
+        let offset = scroll_offset(self.issue_offset, row, self.issues.usable_size().height);
+        if offset != self.issue_offset {
+            self.issue_offset = offset;
+            self.sync_issues_window();
+        }
+
+        self.issues.set_selected(Some(row - self.issue_offset));
+
+        self.sync_issue_description_window();
+        self.sync_comments_window();
+    }
+
+    pub fn move_sprint_selection_down(&mut self) {
+        let row = self
+            .sprint_filter
+            .row(self.active_sprint)
+            .map_or(0, |row| row + 1);
+
+        if let Some(&index) = self.sprint_filter.rows.get(row) {
+            self.select_sprint(index);
+        }
+    }
+
+    pub fn move_sprint_selection_up(&mut self) {
+        let row = self.sprint_filter.row(self.active_sprint);
+
+        if let Some(&index) = row
+            .and_then(|row| row.checked_sub(1))
+            .and_then(|row| self.sprint_filter.rows.get(row))
+        {
+            self.select_sprint(index);
+        }
+    }
+
+    /// Makes the sprint at `index` the active one, its first issue gets selected.
+    fn select_sprint(&mut self, index: usize) {
+        let Some(row) = self.sprint_filter.row(index) else {
+            return;
+        };
+
+        // Picking a sprint leaves the search
+        self.search = None;
+        self.active_sprint = index;
+        self.active_issue = 0;
+        self.issue_offset = 0;
+
+        let offset = scroll_offset(self.sprint_offset, row, self.sprints.usable_size().height);
+        if offset != self.sprint_offset {
+            self.sprint_offset = offset;
+            self.sync_sprints_window();
+        }
+
+        self.sprints.set_selected(Some(row - self.sprint_offset));
+
+        self.sync_issues_window();
+        self.sync_issue_description_window();
+        self.sync_comments_window();
+    }
+
+    pub fn move_card_selection_down(&mut self) {
+        if let Some(board) = &self.board
+            && let Some(row) = board.active_row(self.active_issue)
+        {
+            self.select_card(board.active_column, row + 1);
+        }
+    }
+
+    pub fn move_card_selection_up(&mut self) {
+        if let Some(board) = &self.board
+            && let Some(row) = board.active_row(self.active_issue)
+            && let Some(row) = row.checked_sub(1)
+        {
+            self.select_card(board.active_column, row);
+        }
+    }
+
+    /// Empty columns are skipped, the card at the same height (or the last one) gets selected.
+    pub fn move_column_selection(&mut self, right: bool) {
+        let Some(board) = &self.board else {
+            return;
+        };
+
+        let row = board.active_row(self.active_issue).unwrap_or(0);
+        let column = match right {
+            true => (board.active_column + 1..board.cards.len())
+                .find(|&column| !board.cards[column].is_empty()),
+            false => (0..board.active_column)
+                .rev()
+                .find(|&column| !board.cards[column].is_empty()),
+        };
+
+        if let Some(column) = column {
+            let row = row.min(board.cards[column].len() - 1);
+            self.select_card(column, row);
+        }
+    }
+
+    /// Makes the card at `row` of `column` the active issue, scrolling it into view.
+    fn select_card(&mut self, column: usize, row: usize) {
+        let Some(board) = &mut self.board else {
+            return;
+        };
+        let Some(&index) = board.cards.get(column).and_then(|cards| cards.get(row)) else {
+            return;
+        };
+
+        let height = board.columns[column].usable_size().height;
+        board.offsets[column] = scroll_offset(board.offsets[column], row, height);
+        board.active_column = column;
+        self.active_issue = index;
+
+        self.sync_board_window();
+        self.sync_issue_description_window();
+        self.sync_comments_window();
+    }
+
+    /// Moves the selected card to the neighbouring column, with the first transition that leads
+    /// to one of its statuses.
+    pub fn move_card(&mut self, right: bool) {
+        let Some(board) = &self.board else {
+            return;
+        };
+        let column = match right {
+            true => board.active_column + 1,
+            false => match board.active_column.checked_sub(1) {
+                Some(column) => column,
+                None => return,
+            },
+        };
+        let Some(target) = self.state.columns.get(column).cloned() else {
+            return;
+        };
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let (issue_id, issue_name) = (issue.id.clone(), issue.name.clone());
+
+        let transitions = match self.jira.get_transitions(&issue_name) {
+            Ok(transitions) => transitions,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the transitions of {issue_name}: {err}"),
+                );
+                return;
+            }
+        };
+
+        let Some(transition) = transitions
+            .into_iter()
+            .find(|transition| target.statuses.contains(&transition.to.id))
+        else {
+            self.log(
+                LogLevel::Error,
+                format!("No transition moves {issue_name} to {}", target.name),
+            );
+            return;
+        };
+
+        self.transition_issues(vec![(issue_id, issue_name, transition)]);
+    }
+
+    /// Clicking a pane focuses it, clicking one of its rows selects it as well.
+    pub fn click(&mut self, position: Vector2) {
+        match self.active_window {
+            // Clicking an entry picks it, clicking anywhere else dismisses the popup
+            Window::Popup => {
+                let Some(popup) = &mut self.popup else {
+                    return;
+                };
+
+                match popup.list.row_at(position) {
+                    Some(row) => {
+                        popup.active = popup.offset + row;
+                        self.confirm_popup();
+                    }
+                    None if !popup.list.contains(position) => self.close_popup(),
+                    None => (),
+                }
+            }
+            Window::Prompt => (),
+            Window::Board => {
+                let Some(board) = &self.board else {
+                    return;
+                };
+
+                if let Some((column, list)) =
+                    (board.columns.iter().enumerate()).find(|(_, list)| list.contains(position))
+                    && let Some(row) = list.row_at(position)
+                {
+                    self.select_card(column, board.offsets[column] + row);
+                }
+            }
+            _ if self.sprints.contains(position) => {
+                self.select_sprints_window();
+                if let Some(&index) = (self.sprints.row_at(position))
+                    .and_then(|row| self.sprint_filter.rows.get(self.sprint_offset + row))
+                {
+                    self.select_sprint(index);
+                }
+            }
+            _ if self.issues.contains(position) => {
+                self.select_issues_window();
+                if let Some(&index) = (self.issues.row_at(position))
+                    .and_then(|row| self.issue_filter.rows.get(self.issue_offset + row))
+                {
+                    self.select_issue(index);
+                }
+            }
+            _ if self.issue_description.contains(position) => {
+                self.select_issue_description_window()
+            }
+            // Focusing the comments fetches them, so we don't do it again on every click
+            Window::Comments => (),
+            _ if self.comments.contains(position) => self.select_comments_window(),
+            _ => (),
+        }
+    }
+
+    /// The wheel moves the selection of the list below the pointer.
+    pub fn scroll(&mut self, position: Vector2, down: bool) {
+        match self.active_window {
+            Window::Popup => match down {
+                true => self.move_popup_selection_down(),
+                false => self.move_popup_selection_up(),
+            },
+            Window::Prompt => (),
+            Window::Board => match down {
+                true => self.move_card_selection_down(),
+                false => self.move_card_selection_up(),
+            },
+            _ if self.sprints.contains(position) => match down {
+                true => self.move_sprint_selection_down(),
+                false => self.move_sprint_selection_up(),
+            },
+            _ if self.issues.contains(position) => match down {
+                true => self.move_issue_selection_down(),
+                false => self.move_issue_selection_up(),
+            },
+            _ if self.issue_description.contains(position) => match down {
+                true => self.issue_description.scroll(Scroll::LineDown),
+                false => self.issue_description.scroll(Scroll::LineUp),
+            },
+            _ if self.comments.contains(position) => match down {
+                true => self.comments.scroll(Scroll::LineDown),
+                false => self.comments.scroll(Scroll::LineUp),
+            },
+            _ => (),
+        }
+    }
+
+    /// Scrolls the focused text pane.
+    pub fn scroll_text(&mut self, scroll: Scroll) {
+        match self.active_window {
+            Window::Description => self.issue_description.scroll(scroll),
+            Window::Comments => self.comments.scroll(scroll),
+            _ => (),
+        }
+    }
+
+    /// Marked issues are moved to a status instead, with whichever transition each of them has.
+    pub fn open_transitions_popup(&mut self) {
+        if !self.marked_issues.is_empty() {
+            self.open_bulk_transitions_popup();
+            return;
+        }
+
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+
+        let (issue_id, issue_name) = (issue.id.clone(), issue.name.clone());
+
+        let transitions = match self.jira.get_transitions(&issue_name) {
+            Ok(transitions) => transitions,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the transitions of {issue_name}: {err}"),
+                );
+                return;
+            }
+        };
+
+        let entries = transitions
+            .iter()
+            .map(|transition| format!("{} → {}", transition.name, transition.to.name))
+            .collect();
+
+        self.open_popup(
+            format!("Transition {issue_name} "),
+            entries,
+            PopupKind::Transitions {
+                issue_id,
+                issue_name,
+                transitions,
+            },
+        );
+    }
+
+    fn open_bulk_transitions_popup(&mut self) {
+        let target_issues = self.target_issues();
+        let subject = issues_subject(&target_issues);
+        let results = concurrently(&self.jira, &target_issues, |jira, issue_name| {
+            jira.get_transitions(issue_name)
+        });
+
+        let mut issues = Vec::with_capacity(target_issues.len());
+        for ((issue_id, issue_name), result) in target_issues.into_iter().zip(results) {
+            match result {
+                Ok(transitions) => issues.push((issue_id, issue_name, transitions)),
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the transitions of {issue_name}: {err}"),
+                ),
+            }
+        }
+
+        let mut statuses: Vec<Status> = Vec::new();
+        for (_, _, transitions) in &issues {
+            for transition in transitions {
+                if !statuses.iter().any(|status| status.id == transition.to.id) {
+                    statuses.push(transition.to.clone());
+                }
+            }
+        }
+
+        if statuses.is_empty() {
+            return;
+        }
+
+        let entries = statuses.iter().map(|status| status.name.clone()).collect();
+
+        self.open_popup(
+            format!("Transition {subject} "),
+            entries,
+            PopupKind::BulkTransitions { issues, statuses },
+        );
+    }
+
+    fn open_popup(&mut self, title: String, entries: Vec<String>, kind: PopupKind) {
+        let mut popup = Popup {
+            kind,
+            list: self.popup_region(&title, &entries).item_list(),
+            title,
+            entries,
+            offset: 0,
+            active: 0,
+            parent: self.active_window,
+        };
+        popup.sync();
+
+        self.popup = Some(popup);
+        self.active_window = Window::Popup;
+    }
+
+    /// Popups are just wide and tall enough to fit their entries.
+    fn popup_region(&self, title: &str, entries: &[String]) -> tui::RenderingRegion {
+        let width = entries
+            .iter()
+            .map(|entry| unicode::width(entry))
+            .chain(iter::once(unicode::width(title) + 2))
+            .max()
+            .unwrap_or(0)
+            + 4;
+
+        let rendering_region = self.terminal.rendering_region();
+        let height = usize::min(entries.len(), rendering_region.size.height / 2) + 2;
+
+        let mut region = rendering_region.floating(width, height);
+        region.set_title(Some(title.into()));
+        region.set_border(Some(Color::Green));
+
+        region
+    }
+
+    pub fn close_popup(&mut self) {
+        if let Some(popup) = self.popup.take() {
+            self.active_window = popup.parent;
+        }
+    }
+
+    pub fn move_popup_selection_down(&mut self) {
+        if let Some(popup) = &mut self.popup {
+            popup.move_selection_down();
+        }
+    }
+
+    pub fn move_popup_selection_up(&mut self) {
+        if let Some(popup) = &mut self.popup {
+            popup.move_selection_up();
+        }
+    }
+
+    /// Ranks the selected issue right before the previous visible one.
+    pub fn rank_issue_up(&mut self) {
+        let previous = (self.issue_filter.row(self.active_issue))
+            .and_then(|row| row.checked_sub(1))
+            .and_then(|row| self.issue_filter.rows.get(row));
+
+        if let Some(&previous) = previous {
+            self.rank_issue(previous, true);
+        }
+    }
+
+    /// Ranks the selected issue right after the next visible one.
+    pub fn rank_issue_down(&mut self) {
+        let next = (self.issue_filter.row(self.active_issue))
+            .and_then(|row| self.issue_filter.rows.get(row + 1));
+
+        if let Some(&next) = next {
+            self.rank_issue(next, false);
+        }
+    }
+
+    /// Ranks the selected issue before every other issue of the sprint, even the filtered out
+    /// ones.
+    pub fn rank_issue_to_top(&mut self) {
+        if self.active_issue > 0 {
+            self.rank_issue(0, true);
+        }
+    }
+
+    /// Ranks the selected issue `before` (or after) the issue at `index` of the sprint.
+    fn rank_issue(&mut self, index: usize, before: bool) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let issue_name = issue.name.clone();
+
+        // The order of the search results and saved queries is the one of their query
+        if self.search.is_some() || self.state.sprint(self.active_sprint).is_none() {
+            self.log(
+                LogLevel::Error,
+                "Issues can only be ranked in a sprint or the backlog",
+            );
+            return;
+        }
+
+        let other_name = self.sprint_issues()[index].name.clone();
+
+        let rank = match before {
+            true => Rank::Before(&other_name),
+            false => Rank::After(&other_name),
+        };
+
+        if let Err(err) = self.jira.rank_issue(&issue_name, rank) {
+            self.log(
+                LogLevel::Error,
+                format!("Could not rank {issue_name}: {err}"),
+            );
+            return;
+        }
+
+        // We reorder it locally so the list doesn't jump around on the next sync. Once the issue
+        // is taken out, the ones after it shift back, so inserting it at `index` puts it right
+        // after the issue it was ranked after
+        if let Some(issues) = self.state.issues.get_mut(self.active_sprint) {
+            let issue = issues.remove(self.active_issue);
+            issues.insert(index, issue);
+        }
+
+        self.sync_issues_window();
+        self.select_issue(index);
+    }
+
+    /// Picks the sprint (or backlog) the marked issues are moved to, or just the selected one if
+    /// none is marked.
+    pub fn open_move_issues_popup(&mut self) {
+        let issues = self.target_issues();
+        if issues.is_empty() {
+            return;
+        }
+
+        let sprints: Vec<Sprint> = self.state.sprints().cloned().collect();
+        let entries = sprints.iter().map(|sprint| sprint.name.clone()).collect();
+
+        self.open_popup(
+            format!("Move {} to ", issues_subject(&issues)),
+            entries,
+            PopupKind::MoveIssues { issues, sprints },
+        );
+    }
+
+    pub fn open_label_prompt(&mut self) {
+        let issues = self.target_issues();
+        if issues.is_empty() {
+            return;
+        }
+
+        self.open_prompt(
+            format!("Label {} ", issues_subject(&issues)),
+            PromptKind::Label { issues },
+        );
+    }
+
+    pub fn open_delete_popup(&mut self) {
+        let issues = self.target_issues();
+        if issues.is_empty() {
+            return;
+        }
+
+        let subject = issues_subject(&issues);
+        let entries = vec!["Cancel".into(), format!("Delete {subject}")];
+
+        self.open_popup(
+            format!("Delete {subject}? "),
+            entries,
+            PopupKind::Delete { issues },
+        );
+    }
+
+    /// The id and name of the marked issues, or of the selected one if none is marked.
+    fn target_issues(&self) -> Vec<(String, String)> {
+        let id_and_name = |issue: &Issue| (issue.id.clone(), issue.name.clone());
+
+        match self.marked_issues.is_empty() {
+            true => self.selected_issue().map(id_and_name).into_iter().collect(),
+            false => {
+                // An issue might be both in a sprint and the search results
+                let mut seen = HashSet::new();
+
+                self.all_issues()
+                    .filter(|issue| self.marked_issues.contains(&issue.id))
+                    .filter(|issue| seen.insert(&issue.id))
+                    .map(id_and_name)
+                    .collect()
+            }
+        }
+    }
+
+    /// Marks every visible issue, or unmarks them if they all are already.
+    pub fn toggle_visible_marks(&mut self) {
+        let issues = self.shown_issues();
+        let visible_ids: Vec<String> = (self.issue_filter.rows.iter())
+            .map(|&index| issues[index].id.clone())
+            .collect();
+
+        if visible_ids.iter().all(|id| self.marked_issues.contains(id)) {
+            for id in &visible_ids {
+                self.marked_issues.remove(id);
+            }
+        } else {
+            self.marked_issues.extend(visible_ids);
+        }
+
+        self.sync_issues_window();
+        self.highlight_active_window();
+    }
+
+    /// Applies the action the popup was opened for with the selected entry.
+    pub fn confirm_popup(&mut self) {
+        let Some(popup) = self.popup.take() else {
+            return;
+        };
+        self.active_window = popup.parent;
+
+        match popup.kind {
+            PopupKind::Transitions {
+                issue_id,
+                issue_name,
+                mut transitions,
+            } => {
+                if popup.active < transitions.len() {
+                    let transition = transitions.swap_remove(popup.active);
+                    self.transition_issues(vec![(issue_id, issue_name, transition)]);
+                }
+            }
+            PopupKind::BulkTransitions { issues, statuses } => {
+                let Some(status) = statuses.get(popup.active) else {
+                    return;
+                };
+
+                let mut transitions = Vec::with_capacity(issues.len());
+                for (issue_id, issue_name, issue_transitions) in issues {
+                    match (issue_transitions.into_iter())
+                        .find(|transition| transition.to.id == status.id)
+                    {
+                        Some(transition) => transitions.push((issue_id, issue_name, transition)),
+                        None => self.log(
+                            LogLevel::Error,
+                            format!("No transition moves {issue_name} to {}", status.name),
+                        ),
+                    }
+                }
+
+                self.transition_issues(transitions);
+            }
+            PopupKind::MoveIssues { issues, sprints } => {
+                if let Some(sprint) = sprints.get(popup.active) {
+                    self.move_issues(&issues, sprint);
+                }
+            }
+            PopupKind::Assignee { issues, users } => {
+                let user = picked_assignee(&users, popup.active).cloned();
+                self.assign_issues(&issues, user);
+            }
+            PopupKind::StartSprint { sprint, update } => {
+                if popup.active == 1 && self.update_sprint(sprint.id, &update) {
+                    self.log(LogLevel::Info, format!("Started {}", sprint.name));
+                }
+            }
+            PopupKind::CompleteSprint {
+                sprint,
+                issues,
+                targets,
+            } => {
+                let Some(entry) = popup.active.checked_sub(1) else {
+                    return;
+                };
+
+                if issues.is_empty() {
+                    self.close_sprint(&sprint);
+                } else if let Some(target) = targets.get(entry)
+                    && self.move_issues(&issues, target)
+                {
+                    self.close_sprint(&sprint);
+                }
+            }
+            PopupKind::Delete { issues } => {
+                if popup.active == 1 {
+                    self.delete_issues(&issues);
+                }
+            }
+            PopupKind::Board { boards } => {
+                if let Some(board) = boards.get(popup.active) {
+                    self.switch_board(board);
+                }
+            }
+            PopupKind::IssueProject {
+                sprint_id,
+                projects,
+            } => {
+                if let Some(project) = projects.get(popup.active) {
+                    let issue = NewIssue {
+                        project: project.key.clone(),
+                        ..Default::default()
+                    };
+                    self.open_issue_type_popup(sprint_id, issue);
+                }
+            }
+            PopupKind::IssueType {
+                sprint_id,
+                mut issue,
+                issue_types,
+            } => {
+                if let Some(issue_type) = issue_types.get(popup.active) {
+                    issue.issue_type_id = issue_type.id.clone();
+                    self.open_prompt(
+                        format!("New {} summary ", issue_type.name),
+                        PromptKind::IssueSummary { sprint_id, issue },
+                    );
+                }
+            }
+            PopupKind::IssueAssignee {
+                sprint_id,
+                mut issue,
+                users,
+            } => {
+                issue.assignee =
+                    picked_assignee(&users, popup.active).map(|user| user.account_id.clone());
+                self.create_issue(sprint_id, issue);
+            }
+        }
+    }
+
+    /// Starts the "create issue" form, which goes through a popup or prompt for every field. The
+    /// issue is created in the active sprint, or in the backlog if a saved query is selected.
+    pub fn open_create_issue_popup(&mut self) {
+        let sprint_id = (self.state.sprint(self.active_sprint)).map_or(0, |sprint| sprint.id);
+
+        let projects = match self.jira.get_create_projects() {
+            Ok(projects) => projects,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the projects: {err}"),
+                );
+                return;
+            }
+        };
+
+        let entries = projects
+            .iter()
+            .map(|project| format!("{} ({})", project.name, project.key))
+            .collect();
+
+        self.open_popup(
+            "New issue project ".into(),
+            entries,
+            PopupKind::IssueProject {
+                sprint_id,
+                projects,
+            },
+        );
+    }
+
+    fn open_issue_type_popup(&mut self, sprint_id: u32, issue: NewIssue) {
+        let issue_types = match self.jira.get_create_issue_types(&issue.project) {
+            Ok(issue_types) => issue_types,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not fetch the issue types of {}: {err}",
+                        issue.project
+                    ),
+                );
+                return;
+            }
+        };
+
+        let entries = issue_types
+            .iter()
+            .map(|issue_type| issue_type.name.clone())
+            .collect();
+
+        self.open_popup(
+            "New issue type ".into(),
+            entries,
+            PopupKind::IssueType {
+                sprint_id,
+                issue,
+                issue_types,
+            },
+        );
+    }
+
+    fn open_issue_assignee_popup(&mut self, sprint_id: u32, issue: NewIssue) {
+        let users = match self
+            .jira
+            .get_assignable_users(Assignable::Project(&issue.project), "")
+        {
+            Ok(users) => users,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the users of {}: {err}", issue.project),
+                );
+                return;
+            }
+        };
+
+        let entries = assignee_entries(&users);
+
+        self.open_popup(
+            "New issue assignee ".into(),
+            entries,
+            PopupKind::IssueAssignee {
+                sprint_id,
+                issue,
+                users,
+            },
+        );
+    }
+
+    fn create_issue(&mut self, sprint_id: u32, issue: NewIssue) {
+        let issue_name = match self.jira.create_issue(&issue) {
+            Ok(issue_name) => issue_name,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not create the issue: {err}"),
+                );
+                return;
+            }
+        };
+
+        self.log(LogLevel::Info, format!("Created {issue_name}"));
+
+        // New issues land in the backlog, the Backlog pseudo-sprint has the id 0
+        if sprint_id != 0
+            && let Err(err) = self.jira.move_issues_to_sprint(sprint_id, &[&issue_name])
+        {
+            self.log(
+                LogLevel::Error,
+                format!("Could not move {issue_name} to the sprint: {err}"),
+            );
+            return;
+        }
+
+        // We add it locally so the user doesn't have to wait for the next sync
+        match self.jira.get_issue(&issue_name) {
+            Ok(issue) => {
+                let sprint_index = self.state.sprint_position(sprint_id);
+
+                if let Some(issues) =
+                    sprint_index.and_then(|index| self.state.issues.get_mut(index))
+                {
+                    issues.push(issue);
+                    self.sync_issues_window();
+                }
+            }
+            Err(err) => self.log(
+                LogLevel::Error,
+                format!("Could not fetch {issue_name}: {err}"),
+            ),
+        }
+    }
+
+    pub fn open_comment_prompt(&mut self) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+        let issue_id = issue.id.clone();
+        let issue_name = issue.name.clone();
+
+        self.open_prompt(
+            format!("New comment on {issue_name} "),
+            PromptKind::Comment {
+                issue_id,
+                issue_name,
+            },
+        );
+    }
+
+    /// Narrows down the focused list as the user types, see `filter` for the syntax.
+    pub fn open_filter_prompt(&mut self) {
+        let (kind, query) = match self.active_window {
+            Window::Issues | Window::Board => {
+                // Queries can mention the current user with '@me'
+                self.myself();
+                (PromptKind::IssueFilter, self.issue_filter.query.clone())
+            }
+            Window::Sprints => (PromptKind::SprintFilter, self.sprint_filter.query.clone()),
+            _ => return,
+        };
+
+        self.open_prompt("Filter ".into(), kind);
+
+        // The current query can be refined instead of typed again
+        if let Some(prompt) = &mut self.prompt {
+            query.chars().for_each(|c| prompt.input.push(c));
+        }
+    }
+
+    /// Searches issues with a JQL query, from any project or board.
+    pub fn open_search_prompt(&mut self) {
+        let jql = self.search.as_ref().map(|search| search.jql.clone());
+
+        self.open_prompt("JQL search ".into(), PromptKind::Search);
+
+        // The last query can be refined instead of typed again
+        if let Some(prompt) = &mut self.prompt {
+            jql.unwrap_or_default()
+                .chars()
+                .for_each(|c| prompt.input.push(c));
+        }
+    }
+
+    fn search(&mut self, jql: &str) {
+        let results = match self.jira.search_issues(jql) {
+            Ok(results) => results,
+            Err(err) => {
+                self.log(LogLevel::Error, format!("Could not search '{jql}': {err}"));
+                return;
+            }
+        };
+
+        self.log(
+            LogLevel::Info,
+            format!("Found {} issues for '{jql}'", results.total),
+        );
+
+        self.search = Some(Search {
+            jql: jql.into(),
+            issues: results.issues,
+            total: results.total,
+        });
+        self.active_issue = 0;
+        self.issue_offset = 0;
+
+        self.select_issues_window();
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Shows the issues of the active sprint again.
+    pub fn close_search(&mut self) {
+        if self.search.take().is_none() {
+            return;
+        }
+
+        self.active_issue = 0;
+        self.issue_offset = 0;
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Picks the board to show, the boards are narrowed down by name or project first.
+    pub fn open_board_prompt(&mut self) {
+        self.open_prompt("Board name or project ".into(), PromptKind::Board);
+    }
+
+    /// Lists the boards whose name contains `query` or whose project it is, the configured ones
+    /// go first. Without a query only the configured boards are listed.
+    fn open_boards_popup(&mut self, query: &str) {
+        let boards = match query.is_empty() {
+            true => {
+                let results: Vec<_> = (self.board_ids.iter())
+                    .map(|board_id| (board_id.clone(), self.jira.get_board(board_id)))
+                    .collect();
+
+                // A board that is gone or hidden to the user doesn't hide the others
+                let mut boards = Vec::new();
+                for (board_id, result) in results {
+                    match result {
+                        Ok(board) => boards.push(board),
+                        Err(err) => self.log(
+                            LogLevel::Error,
+                            format!("Could not fetch the board {board_id}: {err}"),
+                        ),
+                    }
+                }
+
+                Ok(boards)
+            }
+            false => self.jira.search_boards(query).map(|mut boards| {
+                // The query is not always a project, so failing here is expected
+                let project_boards = self.jira.get_project_boards(query).unwrap_or_default();
+                for board in project_boards {
+                    if boards.iter().all(|other| other.id != board.id) {
+                        boards.push(board);
+                    }
+                }
+
+                boards
+            }),
+        };
+
+        let mut boards: Vec<AgileBoard> = match boards {
+            Ok(boards) => boards,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the boards: {err}"),
+                );
+                return;
+            }
+        };
+
+        boards.sort_by_key(|board| {
+            let board_id = board.id.to_string();
+            (self.board_ids.iter())
+                .position(|configured| **configured == board_id)
+                .unwrap_or(usize::MAX)
+        });
+
+        if boards.is_empty() {
+            // The failure of every configured board is already logged
+            if !query.is_empty() {
+                self.log(LogLevel::Error, format!("No board matches '{query}'"));
+            }
+            return;
+        }
+
+        let entries = boards
+            .iter()
+            .map(|board| {
+                let active = match *self.board_id == board.id.to_string() {
+                    true => "* ",
+                    false => "  ",
+                };
+                let project = (board.location.as_ref())
+                    .and_then(|location| location.project_key.as_deref())
+                    .map(|key| format!(" ({key})"))
+                    .unwrap_or_default();
+
+                format!("{active}{}{project}", board.name)
+            })
+            .collect();
+
+        self.open_popup("Boards ".into(), entries, PopupKind::Board { boards });
+    }
+
+    /// Shows the cached state of the board until the background sync catches up with it.
+    fn switch_board(&mut self, board: &AgileBoard) {
+        let board_id: Box<str> = board.id.to_string().into();
+        if board_id == self.board_id {
+            return;
+        }
+
+        self.save_state();
+
+        if self.board_sync.send(board_id.clone()).is_err() {
+            self.log(LogLevel::Error, "The background sync is not running");
+        }
+
+        self.state = App::load_state(&board_id).unwrap_or_default();
+        self.board_id = board_id;
+
+        // Nothing picked on the previous board applies to this one
+        self.search = None;
+        self.marked_issues.clear();
+        self.sprint_filter.query.clear();
+        self.issue_filter.query.clear();
+        self.active_sprint = 0;
+        self.sprint_offset = 0;
+        self.active_issue = 0;
+        self.issue_offset = 0;
+
+        self.log(LogLevel::Info, format!("Switched to board {}", board.name));
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Escaping the Issues pane clears its filter first, then leaves the search.
+    pub fn clear_filter_or_search(&mut self) {
+        match self.issue_filter.is_active() || self.search.is_none() {
+            true => self.clear_filter(),
+            false => self.close_search(),
+        }
+    }
+
+    /// Shows every item of the focused list again.
+    pub fn clear_filter(&mut self) {
+        match self.active_window {
+            Window::Issues | Window::Board => self.issue_filter.query.clear(),
+            Window::Sprints => self.sprint_filter.query.clear(),
+            _ => return,
+        }
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Filter prompts narrow down their list on every keystroke.
+    fn update_filter(&mut self) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+
+        let query = prompt.input.text().to_string();
+        match prompt.kind {
+            PromptKind::IssueFilter => self.issue_filter.query = query,
+            PromptKind::SprintFilter => self.sprint_filter.query = query,
+            _ => return,
+        }
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    fn open_prompt(&mut self, title: String, kind: PromptKind) {
+        self.prompt = Some(Prompt {
+            input: self.prompt_region(&title, &kind).input(),
+            kind,
+            title,
+            parent: self.active_window,
+        });
+        self.active_window = Window::Prompt;
+    }
+
+    /// Filters are typed over the bottom of their list, to keep the rest of it visible.
+    fn prompt_region(&self, title: &str, kind: &PromptKind) -> tui::RenderingRegion {
+        let mut region = match kind {
+            PromptKind::IssueFilter => self.issues.overlay(3),
+            PromptKind::SprintFilter => self.sprints.overlay(3),
+            _ => {
+                let rendering_region = self.terminal.rendering_region();
+                let width = rendering_region.size.width * 3 / 5;
+
+                rendering_region.floating(width, 3)
+            }
+        };
+        region.set_title(Some(title.into()));
+        region.set_border(Some(Color::Green));
+
+        region
+    }
+
+    pub fn close_prompt(&mut self) {
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+        self.active_window = prompt.parent;
+
+        // Cancelling a filter shows everything again
+        if matches!(
+            prompt.kind,
+            PromptKind::IssueFilter | PromptKind::SprintFilter
+        ) {
+            self.clear_filter();
+        }
+    }
+
+    pub fn push_prompt_character(&mut self, c: char) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input.push(c);
+        }
+
+        self.update_filter();
+    }
+
+    pub fn pop_prompt_character(&mut self) {
+        if let Some(prompt) = &mut self.prompt {
+            prompt.input.pop();
+        }
+
+        self.update_filter();
+    }
+
+    /// Applies the action the prompt was opened for with the typed text.
+    pub fn confirm_prompt(&mut self) {
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+        self.active_window = prompt.parent;
+
+        let text = prompt.input.text().trim();
+
+        match prompt.kind {
+            PromptKind::Comment {
+                issue_id,
+                issue_name,
+            } => {
+                if !text.is_empty() {
+                    self.add_comment(&issue_id, &issue_name, text);
+                }
+            }
+            PromptKind::Label { issues } => {
+                if text.is_empty() || text.contains(char::is_whitespace) {
+                    self.log(LogLevel::Error, "Labels can't be empty or contain spaces");
+                    return;
+                }
+
+                self.add_label(&issues, text);
+            }
+            // The list was filtered while typing, so the filter is just kept
+            PromptKind::IssueFilter | PromptKind::SprintFilter => (),
+            PromptKind::Search => {
+                if text.is_empty() {
+                    self.log(LogLevel::Error, "The JQL query can't be empty");
+                    return;
+                }
+
+                self.search(text);
+            }
+            PromptKind::Board => self.open_boards_popup(text),
+            PromptKind::IssueSummary {
+                sprint_id,
+                mut issue,
+            } => {
+                if text.is_empty() {
+                    self.log(LogLevel::Error, "The issue summary can't be empty");
+                    return;
+                }
+
+                issue.summary = text.into();
+                self.open_prompt(
+                    "New issue description (optional) ".into(),
+                    PromptKind::IssueDescription { sprint_id, issue },
+                );
+            }
+            PromptKind::IssueDescription {
+                sprint_id,
+                mut issue,
+            } => {
+                issue.description = text.into();
+                self.open_issue_assignee_popup(sprint_id, issue);
+            }
+        }
+    }
+
+    fn add_comment(&mut self, issue_id: &str, issue_name: &str, body: &str) {
+        match self.jira.add_comment(issue_name, body) {
+            Ok(comment) => {
+                // The selection might have moved to another issue while typing
+                if let Some((id, comments)) = &mut self.issue_comments
+                    && id == issue_id
+                {
+                    comments.push(comment);
+                }
+
+                self.log(LogLevel::Info, format!("Commented on {issue_name}"));
+                self.sync_comments_window();
+            }
+            Err(err) => self.log(
+                LogLevel::Error,
+                format!("Could not comment on {issue_name}: {err}"),
+            ),
+        }
+    }
+
+    pub fn open_assignee_popup(&mut self) {
+        let issues = self.target_issues();
+        let Some((_, issue_name)) = issues.first() else {
+            return;
+        };
+
+        // Marked issues might be from other projects, the first one is the best guess we have
+        let users = match self
+            .jira
+            .get_assignable_users(Assignable::Issue(issue_name), "")
+        {
+            Ok(users) => users,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the users of {issue_name}: {err}"),
+                );
+                return;
+            }
+        };
+
+        let entries = assignee_entries(&users);
+
+        self.open_popup(
+            format!("Assign {} ", issues_subject(&issues)),
+            entries,
+            PopupKind::Assignee { issues, users },
+        );
+    }
+
+    pub fn assign_selected_issue_to_myself(&mut self) {
+        let issues = self.target_issues();
+        if issues.is_empty() {
+            return;
+        }
+
+        if let Some(myself) = self.myself() {
+            self.assign_issues(&issues, Some(myself));
+        }
+    }
+
+    /// The user behind the configured credentials, fetched the first time it's needed.
+    fn myself(&mut self) -> Option<User> {
+        if self.myself.is_none() {
+            match self.jira.get_myself() {
+                Ok(user) => self.myself = Some(user),
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!("Could not fetch the current user: {err}"),
+                ),
+            }
+        }
+
+        self.myself.clone()
+    }
+
+    fn assign_issues(&mut self, issues: &[(String, String)], user: Option<User>) {
+        let account_id = user.as_ref().map(|user| user.account_id.as_str());
+
+        let assigned = self.for_each_issue(
+            issues,
+            "assign",
+            |subject| match &user {
+                Some(user) => format!("Assigned {subject} to {}", user.display_name),
+                None => format!("Unassigned {subject}"),
+            },
+            |jira, issue_name| jira.assign_issue(issue_name, account_id),
+        );
+
+        // We update them locally so the user doesn't have to wait for the next sync
+        for issue in self.all_issues_mut() {
+            if assigned.contains(&issue.id) {
+                issue.fields.assignee = user.clone();
+            }
+        }
+
+        self.sync_issues_window();
+        self.highlight_active_window();
+    }
+
+    /// Opens the summary, assignee and description of the selected issue in the user's editor and
+    /// sends back whatever was changed.
+    pub fn edit_issue(&mut self) {
+        let Some(issue) = self.selected_issue() else {
+            return;
+        };
+
+        let issue_id = issue.id.clone();
+        let issue_name = issue.name.clone();
+        let summary = issue.fields.summary.clone();
+        let assignee = issue
+            .fields
+            .assignee
+            .as_ref()
+            .map(|assignee| assignee.display_name.clone())
+            .unwrap_or_default();
+        let description = issue
+            .fields
+            .description
+            .as_ref()
+            .map(|description| description.to_wiki())
+            .unwrap_or_default();
+
+        let content = editor::template(
+            &[("Summary", &summary), ("Assignee", &assignee)],
+            "Lines starting with '#' are ignored, leave the assignee empty to unassign the issue",
+            "description",
+            &description,
+        );
+
+        let content = match editor::edit(&mut self.terminal, &issue_name, &content) {
+            Ok(content) => content,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not edit {issue_name}: {err}"),
+                );
+                return;
+            }
+        };
+
+        let (fields, new_description) = editor::parse(&content);
+        let new_summary = fields.get("Summary").cloned().unwrap_or_default();
+        let new_assignee = fields.get("Assignee").cloned().unwrap_or_default();
+
+        let mut update = IssueUpdate::default();
+
+        if new_summary.is_empty() {
+            self.log(LogLevel::Error, "The issue summary can't be empty");
+            return;
+        } else if new_summary != summary {
+            update.summary = Some(new_summary);
+        }
+
+        if new_description != description.trim_end() {
+            update.description = Some(new_description);
+        }
+
+        // The user types a name, but Jira wants the account id
+        let mut new_assignee_user = None;
+        if new_assignee.is_empty() && !assignee.is_empty() {
+            update.assignee = Some(None);
+        } else if new_assignee != assignee {
+            let users = match self
+                .jira
+                .get_assignable_users(Assignable::Issue(&issue_name), &new_assignee)
+            {
+                Ok(users) => users,
+                Err(err) => {
+                    self.log(LogLevel::Error, format!("Could not fetch the users: {err}"));
+                    return;
+                }
+            };
+
+            let Some(user) = users
+                .into_iter()
+                .find(|user| user.display_name.eq_ignore_ascii_case(&new_assignee))
+            else {
+                self.log(
+                    LogLevel::Error,
+                    format!("No user named '{new_assignee}' can be assigned to {issue_name}"),
+                );
+                return;
+            };
+
+            update.assignee = Some(Some(user.account_id.clone()));
+            new_assignee_user = Some(user);
+        }
+
+        if update.is_empty() {
+            self.log(LogLevel::Info, format!("{issue_name} was not changed"));
+            return;
+        }
 
-        self.sync_issue_description_window();
+        if let Err(err) = self.jira.update_issue(&issue_name, &update) {
+            self.log(
+                LogLevel::Error,
+                format!("Could not update {issue_name}: {err}"),
+            );
+            return;
+        }
+
+        // We update it locally so the user doesn't have to wait for the next sync
+        for issue in self.all_issues_mut().filter(|issue| issue.id == issue_id) {
+            if let Some(summary) = &update.summary {
+                issue.fields.summary = summary.clone();
+            }
+
+            if let Some(description) = &update.description {
+                issue.fields.description = Some(Description::Wiki(description.clone()));
+            }
+
+            if update.assignee.is_some() {
+                issue.fields.assignee = new_assignee_user.clone();
+            }
+        }
+
+        self.log(LogLevel::Info, format!("Updated {issue_name}"));
+        self.sync_state();
     }
 
-    pub fn move_issue_selection_up(&mut self) {
-        if self.active_issue == 0 {
-            return;
+    /// The Backlog pseudo-sprint has its own endpoint, issues can't be moved to it as a sprint.
+    /// Every issue is moved with a single request.
+    fn move_issues(&mut self, issues: &[(String, String)], sprint: &Sprint) -> bool {
+        let issue_keys: Vec<&str> = issues.iter().map(|(_, name)| name.as_str()).collect();
+        let moved = issues_subject(issues);
+
+        let result = match sprint.id {
+            0 => self.jira.move_issues_to_backlog(&issue_keys),
+            sprint_id => self.jira.move_issues_to_sprint(sprint_id, &issue_keys),
         };
 
-        self.active_issue -= 1;
+        if let Err(err) = result {
+            self.log(
+                LogLevel::Error,
+                format!("Could not move {moved} to {}: {err}", sprint.name),
+            );
+            return false;
+        }
+
+        let issue_ids: Vec<&String> = issues.iter().map(|(id, _)| id).collect();
+
+        // We move them locally so the user doesn't have to wait for the next sync
+        let selected_issue_id = self.selected_issue().map(|issue| issue.id.clone());
+        let target = self.state.sprint_position(sprint.id);
+
+        if let Some(target) = target {
+            // Saved queries keep their issues, being in a sprint or another doesn't change them
+            let mut moved_issues = Vec::new();
+            for (index, issues) in self.state.issues.iter_mut().enumerate() {
+                let is_sprint =
+                    matches!(self.state.sources.get(index), Some(IssueSource::Sprint(_)));
+                if is_sprint && index != target {
+                    moved_issues
+                        .extend(issues.extract_if(.., |issue| issue_ids.contains(&&issue.id)));
+                }
+            }
 
-        if self.active_issue - self.issue_offset >= self.issues.usable_size().height {
-            self.issue_offset -= 1;
-            self.sync_issues_window();
+            if let Some(issues) = self.state.issues.get_mut(target) {
+                issues.extend(moved_issues);
+            }
         }
 
-        self.issues
-            .set_selected(Some(self.active_issue - self.issue_offset));
-        self.sync_issue_description_window();
+        self.reselect_issue(selected_issue_id);
+        self.marked_issues.retain(|id| !issue_ids.contains(&id));
+
+        self.log(LogLevel::Info, format!("Moved {moved} to {}", sprint.name));
+
+        self.sync_state();
+        self.highlight_active_window();
+
+        true
     }
 
-    pub fn move_sprint_selection_down(&mut self) {
-        if self.active_sprint >= self.state.sprints.len() - 1 {
+    /// Opens the name, dates and goal of a new sprint in the user's editor and creates it as a
+    /// future sprint.
+    pub fn create_sprint(&mut self) {
+        let Some(update) = self.edit_sprint(&Sprint::default(), "new-sprint") else {
+            return;
+        };
+
+        let sprint = match self.jira.create_sprint(&self.board_id, &update) {
+            Ok(sprint) => sprint,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not create the sprint: {err}"),
+                );
+                return;
+            }
+        };
+
+        self.log(LogLevel::Info, format!("Created {}", sprint.name));
+
+        // We add it locally right before the Backlog pseudo-sprint, which is always the last one
+        let current_source = self.state.sources.get(self.active_sprint).cloned();
+        let index = (self.state.sprint_position(0)).unwrap_or(self.state.sources.len());
+
+        self.state
+            .sources
+            .insert(index, IssueSource::Sprint(sprint));
+        let issues_index = index.min(self.state.issues.len());
+        self.state.issues.insert(issues_index, Vec::new());
+
+        self.active_sprint = (self.state.sources.iter())
+            .position(|source| {
+                current_source
+                    .as_ref()
+                    .is_some_and(|current| current.is(source))
+            })
+            .unwrap_or(0);
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Opens the name, dates and goal of the selected sprint in the user's editor and sends back
+    /// whatever was changed.
+    pub fn edit_selected_sprint(&mut self) {
+        let sprint = self.state.sprint(self.active_sprint);
+        let Some(sprint) = sprint.filter(|sprint| sprint.id != 0).cloned() else {
+            self.log(LogLevel::Error, "Only sprints can be edited");
+            return;
+        };
+
+        let Some(update) = self.edit_sprint(&sprint, &format!("sprint-{}", sprint.id)) else {
+            return;
+        };
+
+        if update.is_empty() {
+            self.log(LogLevel::Info, format!("{} was not changed", sprint.name));
             return;
         }
 
-        self.active_sprint += 1;
-        self.active_issue = 0;
+        if self.update_sprint(sprint.id, &update) {
+            self.log(LogLevel::Info, format!("Updated {}", sprint.name));
+        }
+    }
 
-        if self.active_sprint - self.sprint_offset >= self.sprints.usable_size().height {
-            self.sprint_offset += 1;
-            self.sync_sprints_window();
+    /// Lets the user change the fields of `sprint` in their editor, `file_name` tells apart the
+    /// scratch files. Only the changed fields are in the update, there's none if the user made a
+    /// mistake.
+    fn edit_sprint(&mut self, sprint: &Sprint, file_name: &str) -> Option<SprintUpdate> {
+        let day = |date: &Option<String>| {
+            let date = date.as_deref().unwrap_or_default();
+            date.get(..10).unwrap_or(date).to_string()
+        };
+        let (start, end) = (day(&sprint.start_date), day(&sprint.end_date));
+        let goal = sprint.goal.clone().unwrap_or_default();
+
+        let content = editor::template(
+            &[("Name", &sprint.name), ("Start", &start), ("End", &end)],
+            "Lines starting with '#' are ignored, dates are written as YYYY-MM-DD",
+            "goal",
+            &goal,
+        );
+
+        let content = match editor::edit(&mut self.terminal, file_name, &content) {
+            Ok(content) => content,
+            Err(err) => {
+                self.log(LogLevel::Error, format!("Could not edit the sprint: {err}"));
+                return None;
+            }
+        };
+
+        let (fields, new_goal) = editor::parse(&content);
+        let field = |key: &str| fields.get(key).cloned().unwrap_or_default();
+        let (new_name, new_start, new_end) = (field("Name"), field("Start"), field("End"));
+
+        let mut update = SprintUpdate::default();
+
+        if new_name.is_empty() {
+            self.log(LogLevel::Error, "The sprint name can't be empty");
+            return None;
+        } else if new_name != sprint.name {
+            update.name = Some(new_name);
         }
 
-        self.sprints
-            .set_selected(Some(self.active_sprint - self.sprint_offset));
+        // Jira has no way to unset a date, so an empty one is left as it is
+        for (new_date, date, field) in [
+            (new_start, start, &mut update.start_date),
+            (new_end, end, &mut update.end_date),
+        ] {
+            if new_date.is_empty() || new_date == date {
+                continue;
+            }
 
-        self.sync_issues_window();
-        self.sync_issue_description_window();
+            match sprint_date(&new_date) {
+                Some(new_date) => *field = Some(new_date),
+                None => {
+                    self.log(
+                        LogLevel::Error,
+                        format!("'{new_date}' is not a date written as YYYY-MM-DD"),
+                    );
+                    return None;
+                }
+            }
+        }
+
+        if new_goal != goal.trim_end() {
+            update.goal = Some(new_goal);
+        }
+
+        Some(update)
     }
 
-    pub fn move_sprint_selection_up(&mut self) {
-        if self.active_sprint == 0 {
+    /// Future sprints need their dates before they can be started.
+    pub fn start_selected_sprint(&mut self) {
+        let sprint = self.state.sprint(self.active_sprint);
+        let Some(sprint) = sprint.filter(|sprint| sprint.state == "future").cloned() else {
+            self.log(LogLevel::Error, "Only future sprints can be started");
+            return;
+        };
+
+        let (Some(start_date), Some(end_date)) =
+            (sprint.start_date.clone(), sprint.end_date.clone())
+        else {
+            self.log(
+                LogLevel::Error,
+                format!(
+                    "{} needs a start and end date, press 'e' to set them",
+                    sprint.name
+                ),
+            );
+            return;
+        };
+
+        let update = SprintUpdate {
+            state: Some("active".into()),
+            start_date: Some(start_date),
+            end_date: Some(end_date),
+            ..Default::default()
+        };
+
+        let entries = vec!["Cancel".into(), format!("Start {}", sprint.name)];
+
+        self.open_popup(
+            format!("Start {}? ", sprint.name),
+            entries,
+            PopupKind::StartSprint { sprint, update },
+        );
+    }
+
+    /// Unfinished issues have to go somewhere else first, the user picks a future sprint or the
+    /// backlog for them. Either way the user confirms it first.
+    pub fn complete_selected_sprint(&mut self) {
+        let sprint = self.state.sprint(self.active_sprint);
+        let Some(sprint) = sprint.filter(|sprint| sprint.state == "active").cloned() else {
+            self.log(LogLevel::Error, "Only active sprints can be completed");
+            return;
+        };
+
+        // Like Jira, we consider done the issues in the last column of the board
+        let done = (self.state.columns.last())
+            .map(|column| column.statuses.as_slice())
+            .unwrap_or_default();
+
+        let issues: Vec<(String, String)> = (self.sprint_issues().iter())
+            .filter(|issue| !done.contains(&issue.fields.status_id))
+            .map(|issue| (issue.id.clone(), issue.name.clone()))
+            .collect();
+
+        let targets: Vec<Sprint> = (self.state.sprints())
+            .filter(|target| target.state == "future" || target.id == 0)
+            .cloned()
+            .collect();
+
+        let title = match issues.as_slice() {
+            [] => format!("Complete {}? ", sprint.name),
+            [(_, issue_name)] => format!("Move the unfinished {issue_name} to "),
+            issues => format!("Move {} unfinished issues to ", issues.len()),
+        };
+
+        let entries = match issues.is_empty() {
+            true => vec!["Cancel".into(), format!("Complete {}", sprint.name)],
+            false => iter::once("Cancel".to_string())
+                .chain(targets.iter().map(|target| target.name.clone()))
+                .collect(),
+        };
+
+        self.open_popup(
+            title,
+            entries,
+            PopupKind::CompleteSprint {
+                sprint,
+                issues,
+                targets,
+            },
+        );
+    }
+
+    fn close_sprint(&mut self, sprint: &Sprint) {
+        let update = SprintUpdate {
+            state: Some("closed".into()),
+            ..Default::default()
+        };
+
+        if !self.update_sprint(sprint.id, &update) {
             return;
         }
 
-        self.active_issue = 0;
-        self.active_sprint -= 1;
+        // Closed sprints are not shown, the next one gets selected
+        if let Some(index) = self.state.sprint_position(sprint.id) {
+            self.state.sources.remove(index);
+            if index < self.state.issues.len() {
+                self.state.issues.remove(index);
+            }
 
-        if self.active_sprint - self.sprint_offset >= self.sprints.usable_size().height {
-            self.sprint_offset -= 1;
-            self.sync_sprints_window();
+            self.active_sprint = index.min(self.state.sources.len().saturating_sub(1));
+            self.active_issue = 0;
+            self.issue_offset = 0;
+        }
+
+        self.log(LogLevel::Info, format!("Completed {}", sprint.name));
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Sends the `update` and keeps the sprint Jira answers with. Returns whether it worked.
+    fn update_sprint(&mut self, sprint_id: u32, update: &SprintUpdate) -> bool {
+        match self.jira.update_sprint(sprint_id, update) {
+            Ok(sprint) => {
+                if let Some(index) = self.state.sprint_position(sprint_id) {
+                    self.state.sources[index] = IssueSource::Sprint(sprint);
+                }
+
+                self.sync_sprints_window();
+                self.highlight_active_window();
+
+                true
+            }
+            Err(err) => {
+                let sprint = self.state.sprints().find(|s| s.id == sprint_id);
+                let name = sprint.map(|s| s.name.clone()).unwrap_or_default();
+
+                self.log(LogLevel::Error, format!("Could not update {name}: {err}"));
+
+                false
+            }
+        }
+    }
+
+    fn add_label(&mut self, issues: &[(String, String)], label: &str) {
+        let labelled = self.for_each_issue(
+            issues,
+            "label",
+            |subject| format!("Labelled {subject} with {label}"),
+            |jira, issue_name| jira.add_label(issue_name, label),
+        );
+
+        for issue in self.all_issues_mut() {
+            if labelled.contains(&issue.id) && !issue.fields.labels.iter().any(|l| l == label) {
+                issue.fields.labels.push(label.into());
+            }
+        }
+    }
+
+    fn delete_issues(&mut self, issues: &[(String, String)]) {
+        let deleted = self.for_each_issue(
+            issues,
+            "delete",
+            |subject| format!("Deleted {subject}"),
+            |jira, issue_name| jira.delete_issue(issue_name),
+        );
+
+        let selected_issue_id = self.selected_issue().map(|issue| issue.id.clone());
+        let search = self.search.iter_mut().map(|search| &mut search.issues);
+        for issues in self.state.issues.iter_mut().chain(search) {
+            issues.retain(|issue| !deleted.contains(&issue.id));
+        }
+
+        self.reselect_issue(selected_issue_id);
+        self.marked_issues.retain(|id| !deleted.contains(id));
+
+        self.sync_state();
+        self.highlight_active_window();
+    }
+
+    /// Keeps the issue with `issue_id` selected after the issues of its sprint were shuffled, or
+    /// selects the first one if it's gone.
+    fn reselect_issue(&mut self, issue_id: Option<String>) {
+        self.active_issue = (self.shown_issues().iter())
+            .position(|issue| Some(&issue.id) == issue_id.as_ref())
+            .unwrap_or(0);
+    }
+
+    /// Runs `action` on every issue at once and logs how it went. Failures are logged one by one,
+    /// followed by a summary written by `done` for the issues it worked on (e.g. "CAN-1" or "3
+    /// issues"). Returns the ids of those.
+    fn for_each_issue(
+        &mut self,
+        issues: &[(String, String)],
+        verb: &str,
+        done: impl Fn(&str) -> String,
+        action: impl Fn(&Jira, &str) -> Result<(), JiraError> + Sync,
+    ) -> Vec<String> {
+        let results = concurrently(&self.jira, issues, action);
+
+        let mut succeeded = Vec::new();
+        for ((issue_id, issue_name), result) in issues.iter().zip(results) {
+            match result {
+                Ok(()) => succeeded.push((issue_id.clone(), issue_name.clone())),
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!("Could not {verb} {issue_name}: {err}"),
+                ),
+            }
         }
 
-        self.sprints
-            .set_selected(Some(self.active_sprint - self.sprint_offset));
+        if !succeeded.is_empty() {
+            let mut message = done(&issues_subject(&succeeded));
+
+            let failed = issues.len() - succeeded.len();
+            if failed > 0 {
+                message.push_str(&format!(", {failed} failed"));
+            }
+
+            self.log(LogLevel::Info, message);
+        }
+
+        succeeded
+            .into_iter()
+            .map(|(issue_id, _)| issue_id)
+            .collect()
+    }
+
+    /// Applies every transition to its issue (id and name), they all lead to the same status.
+    fn transition_issues(&mut self, transitions: Vec<(String, String, Transition)>) {
+        let Some((_, _, transition)) = transitions.first() else {
+            return;
+        };
+        let status = transition.to.clone();
+
+        let issues: Vec<(String, String)> = (transitions.iter())
+            .map(|(issue_id, issue_name, _)| (issue_id.clone(), issue_name.clone()))
+            .collect();
+        let transition_ids: HashMap<&str, &str> = (transitions.iter())
+            .map(|(_, issue_name, transition)| (issue_name.as_str(), transition.id.as_str()))
+            .collect();
+
+        let transitioned = self.for_each_issue(
+            &issues,
+            "transition",
+            |subject| format!("Moved {subject} to {}", status.name),
+            |jira, issue_name| jira.transition_issue(issue_name, transition_ids[issue_name]),
+        );
+
+        // We update them locally so the user doesn't have to wait for the next sync
+        for issue in self.all_issues_mut() {
+            if transitioned.contains(&issue.id) {
+                issue.fields.status = status.name.clone();
+                issue.fields.status_id = status.id.clone();
+            }
+        }
 
         self.sync_issues_window();
-        self.sync_issue_description_window();
+        self.highlight_active_window();
+    }
+}
+
+/// An outdated or corrupted cache is not worth crashing over, the next sync replaces it.
+fn read_cache<T: DeserializeOwned>(path: &str) -> Option<T> {
+    let file = std::fs::File::open(path).ok()?;
+    serde_json::from_reader(std::io::BufReader::new(file)).ok()
+}
+
+/// Calls `request` for every issue (id and name), a few of them at a time so many marked issues
+/// don't hit the rate limits. The results are in the same order as the issues.
+fn concurrently<T: Send>(
+    jira: &Jira,
+    issues: &[(String, String)],
+    request: impl Fn(&Jira, &str) -> Result<T, JiraError> + Sync,
+) -> Vec<Result<T, JiraError>> {
+    let request = &request;
+    let next = &AtomicUsize::new(0);
+
+    let mut results: Vec<(usize, Result<T, JiraError>)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..MAX_CONCURRENT_REQUESTS.min(issues.len()))
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut results = Vec::new();
+
+                    // Every worker takes the next issue nobody took yet
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some((_, issue_name)) = issues.get(index) else {
+                            break;
+                        };
+                        results.push((index, request(jira, issue_name)));
+                    }
+
+                    results
+                })
+            })
+            .collect();
+
+        (workers.into_iter())
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// How logs and titles refer to a few issues: by name if there's only one of them.
+fn issues_subject(issues: &[(String, String)]) -> String {
+    match issues {
+        [(_, issue_name)] => issue_name.clone(),
+        issues => format!("{} issues", issues.len()),
+    }
+}
+
+/// Describes what changed on every issue of the sprints between two states, along with the id of
+/// the issue. Saved queries are left out, they were never part of the board. `authors` holds who
+/// made the changes we know of by issue id.
+fn state_changes(
+    old: &State,
+    new: &State,
+    authors: &HashMap<String, ChangeAuthors>,
+) -> Vec<(String, String)> {
+    fn sprint_issues(state: &State) -> impl Iterator<Item = (&Issue, &Sprint)> {
+        (state.sources.iter().zip(&state.issues))
+            .filter_map(|(source, issues)| Some((source.sprint()?, issues)))
+            .flat_map(|(sprint, issues)| issues.iter().map(move |issue| (issue, sprint)))
+    }
+
+    // There is nothing to compare to before the first sync
+    if old.sources.is_empty() {
+        return Vec::new();
+    }
+
+    let old_issues: HashMap<&str, (&Issue, &Sprint)> = sprint_issues(old)
+        .map(|(issue, sprint)| (issue.id.as_str(), (issue, sprint)))
+        .collect();
+    let new_ids: HashSet<&str> = sprint_issues(new)
+        .map(|(issue, _)| issue.id.as_str())
+        .collect();
+
+    let mut changes = Vec::new();
+
+    for (issue, sprint) in sprint_issues(new) {
+        let name = &issue.name;
+        let mut change = |description: String| changes.push((issue.id.clone(), description));
+
+        let Some((old_issue, old_sprint)) = old_issues.get(issue.id.as_str()) else {
+            change(format!("{name} added to {}", sprint.name));
+            continue;
+        };
+
+        if old_sprint.id != sprint.id {
+            change(format!(
+                "{name} moved from {} to {}",
+                old_sprint.name, sprint.name
+            ));
+        }
+
+        let (old_fields, fields) = (&old_issue.fields, &issue.fields);
+        let authors = authors.get(&issue.id).cloned().unwrap_or_default();
+        // Nobody is named when the changelog doesn't tell who it was
+        let by = |author: Option<String>| {
+            author
+                .map(|author| format!(" (by {author})"))
+                .unwrap_or_default()
+        };
+
+        if old_fields.status != fields.status {
+            change(format!(
+                "{name} {} → {}{}",
+                old_fields.status,
+                fields.status,
+                by(authors.status)
+            ));
+        }
+
+        let old_assignee = old_fields.assignee.as_ref().map(|user| &user.account_id);
+        if old_assignee != fields.assignee.as_ref().map(|user| &user.account_id) {
+            let by = by(authors.assignee);
+            match &fields.assignee {
+                Some(user) => change(format!("{name} assigned to {}{by}", user.display_name)),
+                None => change(format!("{name} unassigned{by}")),
+            }
+        }
+
+        if old_fields.summary != fields.summary {
+            change(format!(
+                "{name} renamed to '{}'{}",
+                fields.summary,
+                by(authors.summary)
+            ));
+        }
+    }
+
+    for (issue, sprint) in sprint_issues(old) {
+        if !new_ids.contains(issue.id.as_str()) {
+            let description = format!("{} removed from {}", issue.name, sprint.name);
+            changes.push((issue.id.clone(), description));
+        }
+    }
+
+    changes
+}
+
+/// The entries of an assignee popup. The first one leaves the issues unassigned, so the users are
+/// shifted by one.
+fn assignee_entries(users: &[User]) -> Vec<String> {
+    iter::once("Unassigned".to_string())
+        .chain(users.iter().map(|user| user.display_name.clone()))
+        .collect()
+}
+
+/// The user at the `entry` of a popup built by `assignee_entries`, `None` to unassign.
+fn picked_assignee(users: &[User], entry: usize) -> Option<&User> {
+    entry.checked_sub(1).and_then(|index| users.get(index))
+}
+
+/// Dates are typed as YYYY-MM-DD, while Jira wants a time as well.
+fn sprint_date(date: &str) -> Option<String> {
+    let valid = date.len() == 10
+        && (date.bytes().enumerate()).all(|(index, byte)| match index {
+            4 | 7 => byte == b'-',
+            _ => byte.is_ascii_digit(),
+        });
+
+    valid.then(|| format!("{date}T00:00:00.000Z"))
+}
+
+/// The smallest change to `offset` that keeps the `active` row inside a window with `height` rows.
+fn scroll_offset(offset: usize, active: usize, height: usize) -> usize {
+    // The window might be too small to show anything, the active row is still kept in view
+    let height = height.max(1);
+
+    if active < offset {
+        active
+    } else if active >= offset + height {
+        active + 1 - height
+    } else {
+        offset
+    }
+}
+
+impl Board {
+    /// The row of the active issue in the active column.
+    fn active_row(&self, active_issue: usize) -> Option<usize> {
+        (self.cards.get(self.active_column)?)
+            .iter()
+            .position(|&index| index == active_issue)
+    }
+}
+
+impl Popup {
+    fn sync(&mut self) {
+        let entries = self.entries[self.offset..]
+            .iter()
+            .take(self.list.usable_size().height)
+            .cloned()
+            .collect();
+
+        self.list.change_list(entries);
+        self.list.set_selected(Some(self.active - self.offset));
+    }
+
+    fn move_selection_down(&mut self) {
+        if self.active + 1 >= self.entries.len() {
+            return;
+        }
+
+        self.active += 1;
+        self.offset = scroll_offset(self.offset, self.active, self.list.usable_size().height);
+        self.sync();
+    }
+
+    fn move_selection_up(&mut self) {
+        if self.active == 0 {
+            return;
+        }
+
+        self.active -= 1;
+        self.offset = scroll_offset(self.offset, self.active, self.list.usable_size().height);
+        self.sync();
     }
 }
 
 #[derive(Clone, Copy)]
 pub enum Window {
+    Board,
+    Comments,
     Description,
     Issues,
+    Popup,
+    Prompt,
     Sprints,
 }
 
 // TODO: The issue name is cut when it's too long, it might be useful to add it in the description
 //       screen somehow
-// TODO: Add '/' to filter issues or sprints
-// TODO: Add scrolling to description
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{IssueSource, State, state_changes};
+    use crate::jira::{ChangeAuthors, Issue, IssueChange, IssueFields, SavedQuery, Sprint, User};
+
+    fn issue(name: &str, status: &str) -> Issue {
+        Issue {
+            id: name.into(),
+            name: name.into(),
+            fields: IssueFields {
+                summary: format!("Summary of {name}"),
+                kind: "Task".into(),
+                assignee: None,
+                status: status.into(),
+                status_id: String::new(),
+                labels: Vec::new(),
+                description: None,
+            },
+        }
+    }
+
+    fn sprint(id: u32, name: &str) -> IssueSource {
+        IssueSource::Sprint(Sprint {
+            id,
+            name: name.into(),
+            ..Default::default()
+        })
+    }
+
+    /// Two sprints, the backlog and a saved query, all of them with a few issues.
+    fn state() -> State {
+        State {
+            sources: vec![
+                sprint(1, "Sprint 1"),
+                sprint(2, "Sprint 2"),
+                sprint(0, "Backlog"),
+                IssueSource::Query(SavedQuery {
+                    name: "Mine".into(),
+                    jql: "assignee = currentUser()".into(),
+                }),
+            ],
+            issues: vec![
+                vec![issue("CAN-1", "To Do"), issue("CAN-2", "To Do")],
+                vec![issue("CAN-3", "To Do")],
+                vec![issue("CAN-4", "To Do")],
+                vec![issue("CAN-1", "To Do")],
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn change(issue: Issue, sprint_id: Option<u32>) -> IssueChange {
+        IssueChange {
+            issue,
+            sprint_id,
+            authors: ChangeAuthors::default(),
+        }
+    }
+
+    fn names(issues: &[Issue]) -> Vec<&str> {
+        issues.iter().map(|issue| issue.name.as_str()).collect()
+    }
+
+    #[test]
+    fn merge_keeps_the_position_in_the_same_sprint() {
+        let mut state = state();
+        state.merge(vec![change(issue("CAN-1", "Done"), Some(1))]);
+
+        assert_eq!(names(&state.issues[0]), ["CAN-1", "CAN-2"]);
+        assert_eq!(state.issues[0][0].fields.status, "Done");
+        // Saved queries are updated in place
+        assert_eq!(state.issues[3][0].fields.status, "Done");
+    }
+
+    #[test]
+    fn merge_moves_between_sprints() {
+        let mut state = state();
+        state.merge(vec![change(issue("CAN-1", "To Do"), Some(2))]);
+
+        assert_eq!(names(&state.issues[0]), ["CAN-2"]);
+        assert_eq!(names(&state.issues[1]), ["CAN-3", "CAN-1"]);
+        assert_eq!(names(&state.issues[3]), ["CAN-1"]);
+    }
+
+    #[test]
+    fn merge_without_a_known_sprint() {
+        let mut state = state();
+        state.merge(vec![
+            // Done in a closed sprint, it never was on the board
+            change(issue("CAN-9", "Done"), None),
+            // Still in the backlog
+            change(issue("CAN-4", "In Progress"), None),
+            // Moved to a sprint that is not synced yet
+            change(issue("CAN-3", "To Do"), Some(7)),
+        ]);
+
+        assert_eq!(names(&state.issues[1]), Vec::<&str>::new());
+        assert_eq!(names(&state.issues[2]), ["CAN-4"]);
+        assert_eq!(state.issues[2][0].fields.status, "In Progress");
+        assert!(
+            state
+                .issues
+                .iter()
+                .flatten()
+                .all(|issue| issue.name != "CAN-9")
+        );
+    }
+
+    #[test]
+    fn describe_field_changes() {
+        let old = state();
+        let mut new = state();
+
+        new.issues[0][0].fields.status = "In Review".into();
+        new.issues[0][1].fields.assignee = Some(User {
+            account_id: "1".into(),
+            display_name: "Ada Lovelace".into(),
+        });
+        new.issues[1][0].fields.summary = "Fix the logout".into();
+        // The same issue in a saved query is not reported twice
+        new.issues[3][0].fields.status = "In Review".into();
+
+        let changes = state_changes(&old, &new, &HashMap::new());
+        let descriptions: Vec<&str> = changes.iter().map(|(_, change)| change.as_str()).collect();
+
+        assert_eq!(
+            descriptions,
+            [
+                "CAN-1 To Do → In Review",
+                "CAN-2 assigned to Ada Lovelace",
+                "CAN-3 renamed to 'Fix the logout'",
+            ]
+        );
+        assert_eq!(changes[0].0, "CAN-1");
+    }
+
+    #[test]
+    fn describe_who_made_the_changes() {
+        let mut old = state();
+        old.issues[1][0].fields.assignee = Some(User {
+            account_id: "1".into(),
+            display_name: "Ada Lovelace".into(),
+        });
+
+        let mut new = state();
+        new.merge(vec![
+            IssueChange {
+                issue: issue("CAN-1", "In Review"),
+                sprint_id: Some(1),
+                authors: ChangeAuthors {
+                    status: Some("Grace Hopper".into()),
+                    // The summary didn't change since the last sync
+                    summary: Some("Alan Turing".into()),
+                    ..Default::default()
+                },
+            },
+            IssueChange {
+                issue: issue("CAN-3", "To Do"),
+                sprint_id: Some(2),
+                authors: ChangeAuthors {
+                    assignee: Some("Ada Lovelace".into()),
+                    ..Default::default()
+                },
+            },
+        ]);
+
+        let authors = std::mem::take(&mut new.authors);
+        let changes = state_changes(&old, &new, &authors);
+        let descriptions: Vec<&str> = changes.iter().map(|(_, change)| change.as_str()).collect();
+
+        assert_eq!(
+            descriptions,
+            [
+                "CAN-1 To Do → In Review (by Grace Hopper)",
+                "CAN-3 unassigned (by Ada Lovelace)",
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_moved_added_and_removed_issues() {
+        let old = state();
+        let mut new = state();
+
+        let moved = new.issues[0].remove(0);
+        new.issues[1].push(moved);
+        new.issues[2].clear();
+        new.issues[2].push(issue("CAN-5", "To Do"));
+
+        let changes = state_changes(&old, &new, &HashMap::new());
+        let descriptions: Vec<&str> = changes.iter().map(|(_, change)| change.as_str()).collect();
+
+        assert_eq!(
+            descriptions,
+            [
+                "CAN-1 moved from Sprint 1 to Sprint 2",
+                "CAN-5 added to Backlog",
+                "CAN-4 removed from Backlog",
+            ]
+        );
+    }
+
+    #[test]
+    fn no_changes_before_the_first_sync() {
+        assert!(state_changes(&State::default(), &state(), &HashMap::new()).is_empty());
+    }
+}
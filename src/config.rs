@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 use std::{fs::File, io::Read};
 
+use crate::jira::SavedQuery;
+
 const CONFIG_FILEPATH: &str = ".env";
 
 pub struct Config {
     pub user: Box<str>,
     pub token: Box<str>,
-    pub board_id: Box<str>,
+    /// The first one is shown on startup, the others are offered first in the board picker
+    pub board_ids: Vec<Box<str>>,
     pub host: Box<str>,
+    pub queries: Vec<SavedQuery>,
 }
 
 /// Extract the configuration struct from the environment variables or the `.env` file, giving
@@ -25,17 +29,102 @@ pub fn configuration() -> Result<Config, ()> {
 
     let user = get_variable("JIRA_USER")?;
     let token = get_variable("JIRA_TOKEN")?;
-    let board_id = get_variable("JIRA_BOARD_ID")?;
+    let board_ids = parse_board_ids(&get_variable("JIRA_BOARD_ID")?);
     let host = get_variable("JIRA_HOST")?;
 
+    if board_ids.is_empty() {
+        eprintln!("ERROR: JIRA_BOARD_ID doesn't hold any board id");
+        return Err(());
+    }
+
+    // Optional, so it's not an error when it's missing
+    let queries = std::env::var("JIRA_QUERIES")
+        .ok()
+        .or_else(|| variables.remove("JIRA_QUERIES"))
+        .map(|queries| parse_queries(&queries))
+        .transpose()?
+        .unwrap_or_default();
+
     Ok(Config {
         user,
         token,
-        board_id,
+        board_ids,
         host,
+        queries,
     })
 }
 
+/// Parses a comma separated list of board ids, e.g. `12, 34`.
+fn parse_board_ids(board_ids: &str) -> Vec<Box<str>> {
+    board_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|board_id| !board_id.is_empty())
+        .map(Box::from)
+        .collect()
+}
+
+/// Parses saved queries in the form `Name: jql; Other name: jql`. A `;` inside a quoted JQL string
+/// or escaped as `\;` doesn't end the query.
+fn parse_queries(queries: &str) -> Result<Vec<SavedQuery>, ()> {
+    split_queries(queries)?
+        .iter()
+        .map(|query| query.trim())
+        .filter(|query| !query.is_empty())
+        .map(|query| {
+            let (name, jql) = query
+                .split_once(':')
+                .map(|(name, jql)| (name.trim(), jql.trim()))
+                .filter(|(name, jql)| !name.is_empty() && !jql.is_empty())
+                .ok_or_else(|| {
+                    eprintln!("ERROR: JIRA_QUERIES entry \"{query}\" is not in the form Name: jql")
+                })?;
+
+            Ok(SavedQuery {
+                name: name.to_string(),
+                jql: jql.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Splits the queries on the `;` that are outside of quoted strings and not escaped.
+fn split_queries(queries: &str) -> Result<Vec<String>, ()> {
+    let mut split = vec![String::new()];
+    let mut quote = None;
+    let mut chars = queries.chars();
+
+    while let Some(char) = chars.next() {
+        let query = split.last_mut().unwrap();
+
+        match (char, quote) {
+            // Escaped separators lose the backslash, the others are kept for the JQL
+            ('\\', None) if chars.clone().next() == Some(';') => query.extend(chars.next()),
+            ('\\', _) => {
+                query.push(char);
+                query.extend(chars.next());
+            }
+            (';', None) => split.push(String::new()),
+            ('"' | '\'', None) => {
+                quote = Some(char);
+                query.push(char);
+            }
+            (char, Some(open)) if char == open => {
+                quote = None;
+                query.push(char);
+            }
+            (char, _) => query.push(char),
+        }
+    }
+
+    if quote.is_some() {
+        eprintln!("ERROR: JIRA_QUERIES has a quoted string that is never closed");
+        return Err(());
+    }
+
+    Ok(split)
+}
+
 fn parse_dotenv() -> Result<HashMap<String, String>, ()> {
     let mut variables = HashMap::with_capacity(2);
     let mut content = Vec::new();
@@ -61,17 +150,28 @@ fn parse_variable(content: &mut &[u8]) -> Result<(String, String), ()> {
         variable_index += 1;
     }
 
-    let mut value_index = variable_index + 1;
-    while value_index < content.len() && !content[value_index].is_ascii_whitespace() {
-        value_index += 1;
+    let mut value_start = (variable_index + 1).min(content.len());
+    let mut value_end = value_start;
+
+    // Quoted values can contain whitespaces
+    if content.get(value_start) == Some(&b'"') {
+        value_start += 1;
+        value_end = value_start;
+        while value_end < content.len() && content[value_end] != b'"' {
+            value_end += 1;
+        }
+    } else {
+        while value_end < content.len() && !content[value_end].is_ascii_whitespace() {
+            value_end += 1;
+        }
     }
 
     let variable = String::from_utf8(content[0..variable_index].to_vec())
         .map_err(|err| eprintln!("ERROR: the content is not utf8 encoded: {err}"))?;
-    let value = String::from_utf8(content[variable_index + 1..value_index].to_vec())
+    let value = String::from_utf8(content[value_start..value_end].to_vec())
         .map_err(|err| eprintln!("ERROR: the content is not utf8 encoded: {err}"))?;
 
-    *content = &content[value_index + 1..];
+    *content = &content[(value_end + 1).min(content.len())..];
 
     Ok((variable, value))
 }
@@ -84,3 +184,62 @@ fn trim_left_whitespaces(content: &mut &[u8]) {
 
     *content = &content[index..]
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_quoted_variables() {
+        let mut content =
+            "JIRA_QUERIES=\"Mine: assignee = currentUser()\"\nJIRA_HOST=host".as_bytes();
+
+        let (variable, value) = parse_variable(&mut content).unwrap();
+        assert_eq!(variable, "JIRA_QUERIES");
+        assert_eq!(value, "Mine: assignee = currentUser()");
+
+        trim_left_whitespaces(&mut content);
+        let (variable, value) = parse_variable(&mut content).unwrap();
+        assert_eq!(variable, "JIRA_HOST");
+        assert_eq!(value, "host");
+        assert!(content.is_empty());
+    }
+
+    #[test]
+    fn parse_board_id_lists() {
+        assert_eq!(parse_board_ids("12"), vec![Box::from("12")]);
+        assert_eq!(
+            parse_board_ids("12, 34,,56 "),
+            vec![Box::from("12"), Box::from("34"), Box::from("56")]
+        );
+    }
+
+    #[test]
+    fn parse_saved_queries() {
+        let queries = parse_queries("Mine: assignee = currentUser(); Bugs: type = Bug;").unwrap();
+
+        assert_eq!(queries.len(), 2);
+        assert_eq!(queries[0].name, "Mine");
+        assert_eq!(queries[0].jql, "assignee = currentUser()");
+        assert_eq!(queries[1].name, "Bugs");
+        assert_eq!(queries[1].jql, "type = Bug");
+    }
+
+    #[test]
+    fn parse_saved_queries_with_separators_in_the_jql() {
+        let queries =
+            parse_queries(r#"Quoted: summary ~ "a;b"; Single: text ~ 'c;d'; Escaped: text ~ e\;f"#)
+                .unwrap();
+
+        assert_eq!(queries.len(), 3);
+        assert_eq!(queries[0].jql, r#"summary ~ "a;b""#);
+        assert_eq!(queries[1].jql, "text ~ 'c;d'");
+        assert_eq!(queries[2].jql, "text ~ e;f");
+    }
+
+    #[test]
+    fn reject_malformed_saved_queries() {
+        assert!(parse_queries("Mine: assignee = currentUser(); type = Bug").is_err());
+        assert!(parse_queries(r#"Open: summary ~ "a;b"#).is_err());
+    }
+}
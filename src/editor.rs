@@ -0,0 +1,125 @@
+//! Editing text in the user's `$EDITOR`.
+//! The text is laid out as a small header of `Key: value` fields followed by a free-form body,
+//! which is how we let the user change several fields of something at once.
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use crate::tui::{ResizeSignal, Terminal};
+
+/// Everything after this line is the body
+const BODY_SEPARATOR: &str = "# ------------------------ Everything below is the ";
+
+/// Suspends the terminal, opens `content` in the user's editor and returns what was saved.
+pub fn edit(terminal: &mut Terminal, name: &str, content: &str) -> std::io::Result<String> {
+    let path = std::env::temp_dir().join(format!("canoa-{}-{name}.txt", std::process::id()));
+
+    // The temporary directory is shared, so a file that is already there (or a symlink) might
+    // belong to someone else and it's never reused
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)?
+        .write_all(content.as_bytes())?;
+
+    // The variable might carry arguments, e.g. 'code --wait'
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".into());
+    let mut arguments = editor.split_whitespace();
+    let program = arguments.next().unwrap_or("vi");
+
+    let mut command = Command::new(program);
+    command.args(arguments).arg(&path);
+
+    // SAFETY: Unblocking a signal is async-signal-safe
+    unsafe { command.pre_exec(ResizeSignal::unblock) };
+
+    terminal.suspend()?;
+    let status = command.status();
+    terminal.resume()?;
+
+    let result = match status {
+        Ok(status) if status.success() => std::fs::read_to_string(&path),
+        Ok(status) => Err(std::io::Error::other(format!(
+            "{program} exited with {status}"
+        ))),
+        Err(err) => Err(err),
+    };
+
+    // It's only a scratch file, failing to remove it is not worth reporting
+    let _ = std::fs::remove_file(&path);
+
+    result
+}
+
+/// Lays out the `fields` as a header, with a `help` comment, followed by the `body`.
+pub fn template(fields: &[(&str, &str)], help: &str, body_name: &str, body: &str) -> String {
+    let mut content = String::new();
+
+    for (key, value) in fields {
+        content.push_str(&format!("{key}: {value}\n"));
+    }
+
+    for line in help.lines() {
+        content.push_str(&format!("# {line}\n"));
+    }
+
+    content.push_str(&format!("{BODY_SEPARATOR}{body_name}\n"));
+    content.push_str(body);
+
+    content
+}
+
+/// The inverse of `template`, comments in the header are ignored.
+pub fn parse(content: &str) -> (HashMap<String, String>, String) {
+    let (header, body) = match content.find(BODY_SEPARATOR) {
+        Some(position) => {
+            let body = &content[position..];
+            let body = body.split_once('\n').map(|(_, body)| body).unwrap_or("");
+            (&content[..position], body)
+        }
+        None => (content, ""),
+    };
+
+    let fields = header
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    (fields, body.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, template};
+
+    #[test]
+    fn parse_template() {
+        let content = template(
+            &[("Summary", "Fix the login"), ("Assignee", "")],
+            "Lines starting with '#' are ignored",
+            "description",
+            "It fails\n\nwith: an error\n",
+        );
+
+        let (fields, body) = parse(&content);
+
+        assert_eq!(fields["Summary"], "Fix the login");
+        assert_eq!(fields["Assignee"], "");
+        assert_eq!(body, "It fails\n\nwith: an error");
+    }
+
+    #[test]
+    fn parse_without_body() {
+        let (fields, body) = parse("# comment\nName: Sprint 1\n");
+
+        assert_eq!(fields["Name"], "Sprint 1");
+        assert_eq!(fields.len(), 1);
+        assert!(body.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Queries typed with '/' to narrow down the issues and sprints shown.
+//! A query is a list of terms separated by spaces and an item has to match every one of them.
+//! Terms are looked for case-insensitively in the key, summary, status, type and assignee, unless
+//! they have a prefix: `@me` keeps the issues assigned to the current user, `@name` the ones whose
+//! assignee contains `name`, while `status:` and `type:` only look at that field.
+use crate::jira::Issue;
+
+#[derive(Debug, PartialEq)]
+enum Term {
+    Me,
+    Assignee(String),
+    Status(String),
+    Type(String),
+    Text(String),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Query {
+        let terms = text
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .map(|term| {
+                if term == "@me" {
+                    Term::Me
+                } else if let Some(name) = term.strip_prefix('@') {
+                    Term::Assignee(name.into())
+                } else if let Some(status) = term.strip_prefix("status:") {
+                    Term::Status(status.into())
+                } else if let Some(kind) = term.strip_prefix("type:") {
+                    Term::Type(kind.into())
+                } else {
+                    Term::Text(term)
+                }
+            })
+            .collect();
+
+        Query { terms }
+    }
+
+    /// Whether the query needs to know who the current user is.
+    pub fn mentions_me(&self) -> bool {
+        self.terms.contains(&Term::Me)
+    }
+
+    /// `myself` is the account id of the current user, nothing matches `@me` without it.
+    pub fn matches_issue(&self, issue: &Issue, myself: Option<&str>) -> bool {
+        let assignee = issue.fields.assignee.as_ref();
+        let assignee_name = assignee
+            .map(|assignee| assignee.display_name.to_lowercase())
+            .unwrap_or_default();
+
+        self.terms.iter().all(|term| match term {
+            Term::Me => {
+                assignee.is_some_and(|assignee| Some(assignee.account_id.as_str()) == myself)
+            }
+            Term::Assignee(name) => assignee.is_some() && assignee_name.contains(name),
+            Term::Status(status) => issue.fields.status.to_lowercase().contains(status),
+            Term::Type(kind) => issue.fields.kind.to_lowercase().contains(kind),
+            Term::Text(text) => {
+                [
+                    &issue.name,
+                    &issue.fields.summary,
+                    &issue.fields.status,
+                    &issue.fields.kind,
+                ]
+                .iter()
+                .any(|field| field.to_lowercase().contains(text))
+                    || assignee_name.contains(text)
+            }
+        })
+    }
+
+    /// Sprints and saved queries are only matched by name, so the prefixes are ignored.
+    pub fn matches_name(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+
+        self.terms.iter().all(|term| match term {
+            Term::Text(text) => name.contains(text),
+            _ => true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Query;
+    use crate::jira::{Issue, IssueFields, User};
+
+    fn issue(name: &str, summary: &str, status: &str, assignee: Option<(&str, &str)>) -> Issue {
+        Issue {
+            id: name.into(),
+            name: name.into(),
+            fields: IssueFields {
+                summary: summary.into(),
+                kind: "Bug".into(),
+                assignee: assignee.map(|(account_id, display_name)| User {
+                    account_id: account_id.into(),
+                    display_name: display_name.into(),
+                }),
+                status: status.into(),
+                status_id: String::new(),
+                labels: Vec::new(),
+                description: None,
+            },
+        }
+    }
+
+    #[test]
+    fn match_issues() {
+        let login = issue(
+            "CAN-1",
+            "Fix the login",
+            "In Progress",
+            Some(("1", "Ada Lovelace")),
+        );
+        let logout = issue("CAN-2", "Fix the logout", "To Do", None);
+
+        let query = Query::parse("fix status:progress");
+        assert!(query.matches_issue(&login, None));
+        assert!(!query.matches_issue(&logout, None));
+
+        let query = Query::parse("@me type:bug");
+        assert!(query.mentions_me());
+        assert!(query.matches_issue(&login, Some("1")));
+        assert!(!query.matches_issue(&login, None));
+        assert!(!query.matches_issue(&logout, Some("1")));
+
+        let query = Query::parse("@ada can-");
+        assert!(query.matches_issue(&login, None));
+        assert!(!query.matches_issue(&logout, None));
+
+        assert!(Query::parse("  ").matches_issue(&logout, None));
+    }
+}
@@ -1,17 +1,292 @@
 //! Jira's API implementation
+use std::collections::HashMap;
+use std::fmt::Display;
 use std::iter;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::markup::{self, Document};
+
+/// The fields we request for every issue, anything else is ignored by the deserializer.
+const ISSUE_FIELDS: &str = "summary, status, labels, assignee, issuetype, description";
+
+/// How many items we ask for on every page, the server might cap it to a lower value.
+const PAGE_SIZE: usize = 100;
+
+/// How many pages are requested at the same time, so big resources don't hit the rate limits.
+const MAX_CONCURRENT_PAGES: usize = 4;
+
+/// Searches can match a whole instance, only this many of their issues are fetched.
+const MAX_SEARCH_RESULTS: usize = 500;
+
+/// The board picker only offers this many boards, the user can always type a narrower query.
+const MAX_BOARD_RESULTS: usize = 50;
+
+/// Jira doesn't take more issues than this when moving them to a sprint or the backlog.
+const MAX_ISSUES_PER_MOVE: usize = 50;
+
 pub struct Jira {
     authorization: Box<str>,
     host: Box<str>,
 }
 
-#[derive(Clone, Deserialize, Serialize, Debug)]
+#[derive(Debug)]
+pub enum JiraError {
+    /// The request never got an answer (e.g. DNS failure, connection refused or TLS error).
+    Transport(Box<ureq::Transport>),
+    /// Jira refused the configured credentials.
+    Authentication(u16),
+    /// Jira answered with a non-successful status, `messages` holds the reasons it gave us.
+    Status { code: u16, messages: Vec<String> },
+    /// The response does not match the schema we expect.
+    Deserialization(std::io::Error),
+}
+
+impl Display for JiraError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JiraError::Transport(transport) => write!(f, "could not reach Jira: {transport}"),
+            JiraError::Authentication(code) => write!(
+                f,
+                "Jira rejected the credentials (HTTP {code}), check JIRA_USER and JIRA_TOKEN"
+            ),
+            JiraError::Status { code, messages } if messages.is_empty() => {
+                write!(f, "Jira answered with HTTP {code}")
+            }
+            JiraError::Status { code, messages } => {
+                write!(f, "Jira answered with HTTP {code}: {}", messages.join("; "))
+            }
+            JiraError::Deserialization(err) => write!(f, "could not parse Jira's response: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for JiraError {}
+
+impl From<ureq::Error> for JiraError {
+    fn from(value: ureq::Error) -> Self {
+        match value {
+            ureq::Error::Status(code @ (401 | 403), _) => JiraError::Authentication(code),
+            ureq::Error::Status(code, response) => {
+                /// Jira reports failures as a list of general messages plus field specific ones.
+                #[derive(Deserialize, Default)]
+                struct Body {
+                    #[serde(rename(deserialize = "errorMessages"), default)]
+                    error_messages: Vec<String>,
+                    #[serde(default)]
+                    errors: HashMap<String, String>,
+                }
+
+                let body: Body = response.into_json().unwrap_or_default();
+
+                let mut messages = body.error_messages;
+                messages.extend(
+                    body.errors
+                        .into_iter()
+                        .map(|(field, message)| format!("{field}: {message}")),
+                );
+
+                JiraError::Status { code, messages }
+            }
+            ureq::Error::Transport(transport) => JiraError::Transport(Box::new(transport)),
+        }
+    }
+}
+
+impl From<std::io::Error> for JiraError {
+    fn from(value: std::io::Error) -> Self {
+        JiraError::Deserialization(value)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
 pub struct Sprint {
     pub id: u32,
     pub name: String,
+    /// Either "future", "active" or "closed", the Backlog pseudo-sprint has none
+    #[serde(default)]
+    pub state: String,
+    #[serde(rename = "startDate")]
+    pub start_date: Option<String>,
+    #[serde(rename = "endDate")]
+    pub end_date: Option<String>,
+    pub goal: Option<String>,
+}
+
+/// The first issues matching a JQL query, out of the `total` that match it.
+#[derive(Clone, Debug)]
+pub struct SearchResults {
+    pub issues: Vec<Issue>,
+    pub total: usize,
+}
+
+/// A JQL query saved under a name, either in the config or as a favourite filter in Jira.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SavedQuery {
+    pub name: String,
+    pub jql: String,
+}
+
+/// A workflow transition that can be applied to an issue, moving it `to` another status.
+#[derive(Clone, Deserialize, Debug)]
+pub struct Transition {
+    pub id: String,
+    pub name: String,
+    pub to: Status,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Status {
+    pub id: String,
+    pub name: String,
+}
+
+/// A board of Jira Software, only its project tells apart boards with the same name.
+#[derive(Clone, Deserialize, Debug)]
+pub struct AgileBoard {
+    pub id: u32,
+    pub name: String,
+    #[serde(default)]
+    pub location: Option<BoardLocation>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct BoardLocation {
+    #[serde(rename = "projectKey", default)]
+    pub project_key: Option<String>,
+}
+
+/// A column of the board, which holds the issues in any of its `statuses` (their ids).
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct BoardColumn {
+    pub name: String,
+    #[serde(deserialize_with = "deserialize_column_statuses")]
+    pub statuses: Vec<String>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Comment {
+    #[serde(deserialize_with = "deserialize_assigne")]
+    pub author: Option<String>,
+    /// When it was posted, e.g. '2024-05-01T12:34:56.000+0000'
+    pub created: String,
+    pub body: String,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct Project {
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct IssueType {
+    pub id: String,
+    pub name: String,
+}
+
+/// The same representation is used by the API and the cached state.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct User {
+    #[serde(rename = "accountId")]
+    pub account_id: String,
+    #[serde(rename = "displayName")]
+    pub display_name: String,
+}
+
+/// Everything we need to create an issue, `assignee` holds an account id.
+#[derive(Clone, Debug, Default)]
+pub struct NewIssue {
+    pub project: String,
+    pub issue_type_id: String,
+    pub summary: String,
+    pub description: String,
+    pub assignee: Option<String>,
+}
+
+/// The fields to change on an issue, the ones set to `None` are left untouched. The assignee is
+/// an account id, `Some(None)` unassigns the issue.
+#[derive(Clone, Debug, Default)]
+pub struct IssueUpdate {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub assignee: Option<Option<String>>,
+}
+
+impl IssueUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_none() && self.description.is_none() && self.assignee.is_none()
+    }
+}
+
+/// The fields to change on a sprint, the ones set to `None` are left untouched. Dates are in the
+/// ISO 8601 format, e.g. '2024-05-01T00:00:00.000Z'.
+#[derive(Clone, Debug, Default)]
+pub struct SprintUpdate {
+    pub name: Option<String>,
+    pub goal: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub state: Option<String>,
+}
+
+impl SprintUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.goal.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+            && self.state.is_none()
+    }
+
+    fn fields(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut fields = serde_json::Map::new();
+
+        let values = [
+            ("name", &self.name),
+            ("goal", &self.goal),
+            ("startDate", &self.start_date),
+            ("endDate", &self.end_date),
+            ("state", &self.state),
+        ];
+
+        for (key, value) in values {
+            if let Some(value) = value {
+                fields.insert(key.into(), value.as_str().into());
+            }
+        }
+
+        fields
+    }
+}
+
+/// Who can be assigned depends on the project or, more precisely, on the issue
+pub enum Assignable<'a> {
+    Project(&'a str),
+    Issue(&'a str),
+}
+
+/// Where an issue is ranked, relative to another issue (its key). Ranks are the order of the
+/// backlog and the sprints.
+pub enum Rank<'a> {
+    Before(&'a str),
+    After(&'a str),
+}
+
+/// Every list endpoint wraps its items in a page. The issue endpoints report the `total`, while
+/// others (e.g. sprints) only tell us whether it `isLast`.
+#[derive(Deserialize)]
+struct Page<T> {
+    #[serde(rename(deserialize = "startAt"), default)]
+    start_at: usize,
+    #[serde(rename(deserialize = "maxResults"), default)]
+    max_results: usize,
+    total: Option<usize>,
+    #[serde(rename(deserialize = "isLast"))]
+    is_last: Option<bool>,
+    #[serde(alias = "issues", alias = "comments", alias = "issueTypes")]
+    values: Vec<T>,
 }
 
 /// The API version contains some special deserializing code to deal with Jira's API.
@@ -21,6 +296,66 @@ pub struct APIIssue {
     #[serde(rename(deserialize = "key"))]
     pub name: String,
     pub fields: APIIssueFields,
+    /// Only there when the changelog is expanded
+    #[serde(default)]
+    changelog: Option<Changelog>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct Changelog {
+    histories: Vec<History>,
+}
+
+/// A set of changes made at once to an issue.
+#[derive(Clone, Deserialize, Debug)]
+struct History {
+    #[serde(default, deserialize_with = "deserialize_assigne")]
+    author: Option<String>,
+    /// When it was made, e.g. '2024-05-01T12:34:56.000+0000'
+    created: String,
+    items: Vec<HistoryItem>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+struct HistoryItem {
+    /// The name of the changed field, e.g. 'status'
+    field: String,
+}
+
+/// An issue of a board that was updated since the last sync.
+#[derive(Clone, Debug)]
+pub struct IssueChange {
+    pub issue: Issue,
+    /// The id of its active or future sprint. Issues in the backlog have none, but so do the ones
+    /// in closed sprints
+    pub sprint_id: Option<u32>,
+    pub authors: ChangeAuthors,
+}
+
+/// Who made the latest change to each of the fields we tell apart between syncs, unknown when
+/// the changelog doesn't have it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeAuthors {
+    pub status: Option<String>,
+    pub assignee: Option<String>,
+    pub summary: Option<String>,
+}
+
+impl From<Changelog> for ChangeAuthors {
+    fn from(value: Changelog) -> Self {
+        let author = |field: &str| {
+            (value.histories.iter())
+                .filter(|history| history.items.iter().any(|item| item.field == field))
+                .max_by(|a, b| a.created.cmp(&b.created))
+                .and_then(|history| history.author.clone())
+        };
+
+        ChangeAuthors {
+            status: author("status"),
+            assignee: author("assignee"),
+            summary: author("summary"),
+        }
+    }
 }
 
 /// The API version contains some special deserializing code to deal with Jira's API.
@@ -32,11 +367,39 @@ pub struct APIIssueFields {
         deserialize_with = "deserialize_type"
     )]
     pub kind: String,
-    #[serde(deserialize_with = "deserialize_assigne")]
-    pub assignee: Option<String>,
-    #[serde(deserialize_with = "deserialize_status")]
-    pub status: String,
-    pub description: Option<String>,
+    pub assignee: Option<User>,
+    pub status: Status,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub description: Option<Description>,
+    /// The active or future sprint of the issue, only the Agile API has it and only when asked
+    #[serde(default)]
+    pub sprint: Option<Sprint>,
+}
+
+/// API v2 returns wiki markup, while API v3 returns the Atlassian Document Format (ADF).
+#[derive(Clone, Deserialize, Serialize, Debug)]
+#[serde(untagged)]
+pub enum Description {
+    Wiki(String),
+    Adf(serde_json::Value),
+}
+
+impl Description {
+    pub fn document(&self) -> Document {
+        match self {
+            Description::Wiki(text) => markup::parse_wiki(text),
+            Description::Adf(node) => markup::parse_adf(node),
+        }
+    }
+
+    /// The description as wiki markup, which is what we send back when editing it.
+    pub fn to_wiki(&self) -> String {
+        match self {
+            Description::Wiki(text) => text.clone(),
+            Description::Adf(_) => self.document().to_wiki(),
+        }
+    }
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -62,7 +425,9 @@ impl From<APIIssueFields> for IssueFields {
             summary: value.summary,
             kind: value.kind,
             assignee: value.assignee,
-            status: value.status,
+            status: value.status.name,
+            status_id: value.status.id,
+            labels: value.labels,
             description: value.description,
         }
     }
@@ -72,9 +437,14 @@ impl From<APIIssueFields> for IssueFields {
 pub struct IssueFields {
     pub summary: String,
     pub kind: String,
-    pub assignee: Option<String>,
+    pub assignee: Option<User>,
     pub status: String,
-    pub description: Option<String>,
+    /// Boards use it to know the column of the issue, older caches don't have it
+    #[serde(default)]
+    pub status_id: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    pub description: Option<Description>,
 }
 
 fn deserialize_type<'de, D>(deserializer: D) -> Result<String, D::Error>
@@ -105,16 +475,26 @@ where
     })
 }
 
-fn deserialize_status<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// The API lists the statuses as objects, we only need their ids. Cached columns already are.
+fn deserialize_column_statuses<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: serde::de::Deserializer<'de>,
 {
     #[derive(Deserialize)]
-    struct Outer {
-        name: String,
+    #[serde(untagged)]
+    enum Outer {
+        Status { id: String },
+        Id(String),
     }
 
-    Outer::deserialize(deserializer).map(|o| o.name)
+    Vec::<Outer>::deserialize(deserializer).map(|statuses| {
+        statuses
+            .into_iter()
+            .map(|status| match status {
+                Outer::Status { id } | Outer::Id(id) => id,
+            })
+            .collect()
+    })
 }
 
 impl Jira {
@@ -125,80 +505,535 @@ impl Jira {
         }
     }
 
-    pub fn get_sprint_issues(&self, board_id: &str, sprint_id: u32) -> Vec<Issue> {
+    pub fn get_transitions(&self, issue_key: &str) -> Result<Vec<Transition>, JiraError> {
         #[derive(Deserialize)]
         struct Response {
-            issues: Vec<APIIssue>,
+            transitions: Vec<Transition>,
         }
 
-        let response: Response = ureq::get(&format!(
-            "{}rest/agile/1.0/board/{board_id}/sprint/{sprint_id}/issue",
-            self.host.as_ref()
-        ))
-        .set("Authorization", self.authorization.as_ref())
-        .query(
-            "fields",
-            "summary, status, labels, assignee, issuetype, description",
+        let response: Response = self
+            .request(
+                "GET",
+                &format!("{}rest/api/2/issue/{issue_key}/transitions", self.host),
+            )
+            .call()?
+            .into_json()?;
+
+        Ok(response.transitions)
+    }
+
+    pub fn transition_issue(&self, issue_key: &str, transition_id: &str) -> Result<(), JiraError> {
+        self.request(
+            "POST",
+            &format!("{}rest/api/2/issue/{issue_key}/transitions", self.host),
         )
-        .call()
-        .unwrap()
-        .into_json()
-        .unwrap();
+        .send_json(serde_json::json!({ "transition": { "id": transition_id } }))?;
 
-        response
-            .issues
-            .into_iter()
-            .map(|issue| issue.into())
-            .collect()
+        Ok(())
     }
 
-    pub fn get_board_active_and_future_sprints(&self, board_id: &str) -> Vec<Sprint> {
+    pub fn rank_issue(&self, issue_key: &str, rank: Rank) -> Result<(), JiraError> {
+        let body = match rank {
+            Rank::Before(other) => {
+                serde_json::json!({ "issues": [issue_key], "rankBeforeIssue": other })
+            }
+            Rank::After(other) => {
+                serde_json::json!({ "issues": [issue_key], "rankAfterIssue": other })
+            }
+        };
+
+        self.request("PUT", &format!("{}rest/agile/1.0/issue/rank", self.host))
+            .send_json(body)?;
+
+        Ok(())
+    }
+
+    pub fn get_comments(&self, issue_key: &str) -> Result<Vec<Comment>, JiraError> {
+        let url = format!("{}rest/api/2/issue/{issue_key}/comment", self.host);
+
+        self.get_all_pages(&url, &[("orderBy", "created")])
+    }
+
+    pub fn add_comment(&self, issue_key: &str, body: &str) -> Result<Comment, JiraError> {
+        let comment = self
+            .request(
+                "POST",
+                &format!("{}rest/api/2/issue/{issue_key}/comment", self.host),
+            )
+            .send_json(serde_json::json!({ "body": body }))?
+            .into_json()?;
+
+        Ok(comment)
+    }
+
+    pub fn get_issue(&self, issue_key: &str) -> Result<Issue, JiraError> {
+        let issue: APIIssue = self
+            .request("GET", &format!("{}rest/api/2/issue/{issue_key}", self.host))
+            .query("fields", ISSUE_FIELDS)
+            .call()?
+            .into_json()?;
+
+        Ok(issue.into())
+    }
+
+    /// Creates the issue and returns its key.
+    pub fn create_issue(&self, issue: &NewIssue) -> Result<String, JiraError> {
         #[derive(Deserialize)]
         struct Response {
-            #[serde(rename(deserialize = "values"))]
-            sprints: Vec<Sprint>,
+            key: String,
+        }
+
+        let mut fields = serde_json::json!({
+            "project": { "key": issue.project },
+            "issuetype": { "id": issue.issue_type_id },
+            "summary": issue.summary,
+        });
+
+        if !issue.description.is_empty() {
+            fields["description"] = issue.description.as_str().into();
+        }
+
+        if let Some(assignee) = &issue.assignee {
+            fields["assignee"] = serde_json::json!({ "accountId": assignee });
+        }
+
+        let response: Response = self
+            .request("POST", &format!("{}rest/api/2/issue", self.host))
+            .send_json(serde_json::json!({ "fields": fields }))?
+            .into_json()?;
+
+        Ok(response.key)
+    }
+
+    pub fn get_myself(&self) -> Result<User, JiraError> {
+        let user = self
+            .request("GET", &format!("{}rest/api/2/myself", self.host))
+            .call()?
+            .into_json()?;
+
+        Ok(user)
+    }
+
+    /// Assigns the issue to the account, or unassigns it when there is none.
+    pub fn assign_issue(&self, issue_key: &str, account_id: Option<&str>) -> Result<(), JiraError> {
+        self.request(
+            "PUT",
+            &format!("{}rest/api/2/issue/{issue_key}/assignee", self.host),
+        )
+        .send_json(serde_json::json!({ "accountId": account_id }))?;
+
+        Ok(())
+    }
+
+    pub fn update_issue(&self, issue_key: &str, update: &IssueUpdate) -> Result<(), JiraError> {
+        let mut fields = serde_json::Map::new();
+
+        if let Some(summary) = &update.summary {
+            fields.insert("summary".into(), summary.as_str().into());
+        }
+
+        if let Some(description) = &update.description {
+            fields.insert("description".into(), description.as_str().into());
+        }
+
+        if let Some(assignee) = &update.assignee {
+            let assignee = match assignee {
+                Some(account_id) => serde_json::json!({ "accountId": account_id }),
+                None => serde_json::Value::Null,
+            };
+            fields.insert("assignee".into(), assignee);
+        }
+
+        self.request("PUT", &format!("{}rest/api/2/issue/{issue_key}", self.host))
+            .send_json(serde_json::json!({ "fields": fields }))?;
+
+        Ok(())
+    }
+
+    pub fn add_label(&self, issue_key: &str, label: &str) -> Result<(), JiraError> {
+        self.request("PUT", &format!("{}rest/api/2/issue/{issue_key}", self.host))
+            .send_json(serde_json::json!({ "update": { "labels": [{ "add": label }] } }))?;
+
+        Ok(())
+    }
+
+    pub fn delete_issue(&self, issue_key: &str) -> Result<(), JiraError> {
+        self.request(
+            "DELETE",
+            &format!("{}rest/api/2/issue/{issue_key}", self.host),
+        )
+        .call()?;
+
+        Ok(())
+    }
+
+    /// The projects the user is allowed to create issues in. The createmeta resource that lists
+    /// them isn't paginated, the project search filtered by the create permission is.
+    pub fn get_create_projects(&self) -> Result<Vec<Project>, JiraError> {
+        let url = format!("{}rest/api/2/project/search", self.host);
+
+        self.get_all_pages(&url, &[("action", "create")])
+    }
+
+    /// The issue types the user is allowed to create in the project.
+    pub fn get_create_issue_types(&self, project_key: &str) -> Result<Vec<IssueType>, JiraError> {
+        let url = format!(
+            "{}rest/api/2/issue/createmeta/{project_key}/issuetypes",
+            self.host
+        );
+
+        self.get_all_pages(&url, &[])
+    }
+
+    /// The users that can be assigned, narrowed down to the ones matching the `query` (e.g. by
+    /// name or email) unless it's empty.
+    pub fn get_assignable_users(
+        &self,
+        assignable: Assignable,
+        query: &str,
+    ) -> Result<Vec<User>, JiraError> {
+        let request = self.request(
+            "GET",
+            &format!("{}rest/api/2/user/assignable/search", self.host),
+        );
+
+        let request = match assignable {
+            Assignable::Project(project_key) => request.query("project", project_key),
+            Assignable::Issue(issue_key) => request.query("issueKey", issue_key),
+        };
+
+        // This endpoint returns a bare list instead of a page, so we ask for as much as it allows
+        let users = request
+            .query("query", query)
+            .query("maxResults", "1000")
+            .call()?
+            .into_json()?;
+
+        Ok(users)
+    }
+
+    pub fn move_issues_to_sprint(
+        &self,
+        sprint_id: u32,
+        issue_keys: &[&str],
+    ) -> Result<(), JiraError> {
+        let url = format!("{}rest/agile/1.0/sprint/{sprint_id}/issue", self.host);
+
+        for issue_keys in issue_keys.chunks(MAX_ISSUES_PER_MOVE) {
+            self.request("POST", &url)
+                .send_json(serde_json::json!({ "issues": issue_keys }))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn move_issues_to_backlog(&self, issue_keys: &[&str]) -> Result<(), JiraError> {
+        let url = format!("{}rest/agile/1.0/backlog/issue", self.host);
+
+        for issue_keys in issue_keys.chunks(MAX_ISSUES_PER_MOVE) {
+            self.request("POST", &url)
+                .send_json(serde_json::json!({ "issues": issue_keys }))?;
         }
 
-        let response: Response = ureq::get(&format!(
+        Ok(())
+    }
+
+    pub fn get_sprint_issues(
+        &self,
+        board_id: &str,
+        sprint_id: u32,
+    ) -> Result<Vec<Issue>, JiraError> {
+        let url = format!(
+            "{}rest/agile/1.0/board/{board_id}/sprint/{sprint_id}/issue",
+            self.host.as_ref()
+        );
+
+        let issues = self.get_all_pages::<APIIssue>(&url, &[("fields", ISSUE_FIELDS)])?;
+
+        Ok(issues.into_iter().map(|issue| issue.into()).collect())
+    }
+
+    /// The first issues matching `jql`, from any project or board.
+    pub fn search_issues(&self, jql: &str) -> Result<SearchResults, JiraError> {
+        let url = format!("{}rest/api/2/search", self.host.as_ref());
+
+        let (issues, total) = self.get_pages::<APIIssue>(
+            &url,
+            &[("jql", jql), ("fields", ISSUE_FIELDS)],
+            MAX_SEARCH_RESULTS,
+        )?;
+
+        Ok(SearchResults {
+            issues: issues.into_iter().map(|issue| issue.into()).collect(),
+            total,
+        })
+    }
+
+    /// The issues of the board updated in the last `minutes`.
+    pub fn get_board_issues_updated_since(
+        &self,
+        board_id: &str,
+        minutes: u64,
+    ) -> Result<Vec<IssueChange>, JiraError> {
+        let url = format!(
+            "{}rest/agile/1.0/board/{board_id}/issue",
+            self.host.as_ref()
+        );
+        let jql = format!("updated >= -{minutes}m");
+        let fields = format!("{ISSUE_FIELDS}, sprint");
+
+        let issues = self.get_all_pages::<APIIssue>(
+            &url,
+            &[("jql", &jql), ("fields", &fields), ("expand", "changelog")],
+        )?;
+
+        Ok(issues
+            .into_iter()
+            .map(|mut issue| IssueChange {
+                sprint_id: issue.fields.sprint.as_ref().map(|sprint| sprint.id),
+                authors: (issue.changelog.take())
+                    .map(ChangeAuthors::from)
+                    .unwrap_or_default(),
+                issue: issue.into(),
+            })
+            .collect())
+    }
+
+    /// The filters the user starred in Jira.
+    pub fn get_favourite_filters(&self) -> Result<Vec<SavedQuery>, JiraError> {
+        let filters = self
+            .request(
+                "GET",
+                &format!("{}rest/api/2/filter/favourite", self.host.as_ref()),
+            )
+            .call()?
+            .into_json()?;
+
+        Ok(filters)
+    }
+
+    pub fn get_board_active_and_future_sprints(
+        &self,
+        board_id: &str,
+    ) -> Result<Vec<Sprint>, JiraError> {
+        let url = format!(
             "{}rest/agile/1.0/board/{board_id}/sprint",
             self.host.as_ref()
-        ))
-        .set("Authorization", self.authorization.as_ref())
-        .query("state", "active, future")
-        .call()
-        .unwrap()
-        .into_json()
-        .unwrap();
+        );
+
+        self.get_all_pages(&url, &[("state", "active, future")])
+    }
+
+    /// New sprints are always future ones, they have to be started afterwards.
+    pub fn create_sprint(
+        &self,
+        board_id: &str,
+        sprint: &SprintUpdate,
+    ) -> Result<Sprint, JiraError> {
+        let mut fields = sprint.fields();
+        fields.insert("originBoardId".into(), board_id.into());
+
+        let sprint = self
+            .request("POST", &format!("{}rest/agile/1.0/sprint", self.host))
+            .send_json(fields)?
+            .into_json()?;
+
+        Ok(sprint)
+    }
+
+    /// Sprints are started and completed by changing their state to "active" and "closed".
+    pub fn update_sprint(
+        &self,
+        sprint_id: u32,
+        update: &SprintUpdate,
+    ) -> Result<Sprint, JiraError> {
+        let sprint = self
+            .request(
+                "POST",
+                &format!("{}rest/agile/1.0/sprint/{sprint_id}", self.host),
+            )
+            .send_json(update.fields())?
+            .into_json()?;
+
+        Ok(sprint)
+    }
+
+    pub fn get_board(&self, board_id: &str) -> Result<AgileBoard, JiraError> {
+        let board = self
+            .request(
+                "GET",
+                &format!("{}rest/agile/1.0/board/{board_id}", self.host),
+            )
+            .call()?
+            .into_json()?;
+
+        Ok(board)
+    }
+
+    /// The first boards whose name contains `name`.
+    pub fn search_boards(&self, name: &str) -> Result<Vec<AgileBoard>, JiraError> {
+        let url = format!("{}rest/agile/1.0/board", self.host.as_ref());
 
-        response.sprints
+        self.get_pages(&url, &[("name", name)], MAX_BOARD_RESULTS)
+            .map(|(boards, _)| boards)
     }
 
-    pub fn get_backlog_issues(&self, board_id: &str) -> Vec<Issue> {
+    /// The first boards of the project with the given key or id.
+    pub fn get_project_boards(&self, project: &str) -> Result<Vec<AgileBoard>, JiraError> {
+        let url = format!("{}rest/agile/1.0/board", self.host.as_ref());
+
+        self.get_pages(&url, &[("projectKeyOrId", project)], MAX_BOARD_RESULTS)
+            .map(|(boards, _)| boards)
+    }
+
+    /// The columns of the board, in the order they are shown.
+    pub fn get_board_columns(&self, board_id: &str) -> Result<Vec<BoardColumn>, JiraError> {
         #[derive(Deserialize)]
         struct Response {
-            issues: Vec<APIIssue>,
+            #[serde(rename = "columnConfig")]
+            column_config: ColumnConfig,
+        }
+
+        #[derive(Deserialize)]
+        struct ColumnConfig {
+            columns: Vec<BoardColumn>,
         }
 
-        let response: Response = ureq::get(&format!(
+        let response: Response = self
+            .request(
+                "GET",
+                &format!("{}rest/agile/1.0/board/{board_id}/configuration", self.host),
+            )
+            .call()?
+            .into_json()?;
+
+        Ok(response.column_config.columns)
+    }
+
+    pub fn get_backlog_issues(&self, board_id: &str) -> Result<Vec<Issue>, JiraError> {
+        let url = format!(
             "{}rest/agile/1.0/board/{board_id}/backlog",
             self.host.as_ref()
-        ))
-        .set("Authorization", self.authorization.as_ref())
-        .query(
-            "fields",
-            "summary, status, labels, assignee, issuetype, description",
-        )
-        .call()
-        .unwrap()
-        .into_json()
-        .unwrap();
+        );
 
-        response
-            .issues
-            .into_iter()
-            .map(|issue| issue.into())
-            .collect()
+        let issues = self.get_all_pages::<APIIssue>(&url, &[("fields", ISSUE_FIELDS)])?;
+
+        Ok(issues.into_iter().map(|issue| issue.into()).collect())
     }
+
+    /// Collects every page of a paginated resource.
+    fn get_all_pages<T>(&self, url: &str, query: &[(&str, &str)]) -> Result<Vec<T>, JiraError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        self.get_pages(url, query, usize::MAX)
+            .map(|(values, _)| values)
+    }
+
+    /// Collects the first `limit` items of a paginated resource, along with how many there are.
+    /// When the server tells us the `total` upfront the remaining pages are requested a few at a
+    /// time, otherwise we follow `isLast` one page at a time.
+    fn get_pages<T>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        limit: usize,
+    ) -> Result<(Vec<T>, usize), JiraError>
+    where
+        T: DeserializeOwned + Send,
+    {
+        let first_page: Page<T> = self.get_page(url, query, 0)?;
+
+        // The server is free to return less items than we asked for, so we always use the page
+        // size it reports back.
+        let page_size = usize::max(first_page.max_results, first_page.values.len());
+        let total = first_page.total;
+        let mut values = first_page.values;
+
+        if page_size == 0 {
+            let total = total.unwrap_or(values.len());
+            return Ok((values, total));
+        }
+
+        match total {
+            Some(total) => {
+                let offsets: Vec<_> = remaining_page_offsets(first_page.start_at, page_size, total)
+                    .take_while(|&start_at| start_at < limit)
+                    .collect();
+
+                for offsets in offsets.chunks(MAX_CONCURRENT_PAGES) {
+                    std::thread::scope(|scope| {
+                        let handles: Vec<_> = (offsets.iter())
+                            .map(|&start_at| {
+                                scope.spawn(move || self.get_page::<T>(url, query, start_at))
+                            })
+                            .collect();
+
+                        for handle in handles {
+                            values.extend(handle.join().unwrap()?.values);
+                        }
+
+                        Ok::<(), JiraError>(())
+                    })?;
+                }
+            }
+            None if first_page.is_last == Some(false) => {
+                let mut start_at = first_page.start_at + page_size;
+
+                loop {
+                    let page: Page<T> = self.get_page(url, query, start_at)?;
+                    let page_length = page.values.len();
+                    values.extend(page.values);
+
+                    if page.is_last != Some(false) || page_length == 0 || values.len() >= limit {
+                        break;
+                    }
+
+                    start_at += page_length;
+                }
+            }
+            None => (),
+        }
+
+        // Without a total from the server, all we know is that there are at least these many
+        let total = total.unwrap_or(values.len());
+        values.truncate(limit);
+
+        Ok((values, total))
+    }
+
+    fn get_page<T>(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        start_at: usize,
+    ) -> Result<Page<T>, JiraError>
+    where
+        T: DeserializeOwned,
+    {
+        let page = self
+            .request("GET", url)
+            .query_pairs(query.iter().copied())
+            .query("startAt", &start_at.to_string())
+            .query("maxResults", &PAGE_SIZE.to_string())
+            .call()?
+            .into_json()?;
+
+        Ok(page)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        ureq::request(method, url).set("Authorization", self.authorization.as_ref())
+    }
+}
+
+/// The offsets (`startAt`) of every page that comes after the one starting at `start_at`.
+fn remaining_page_offsets(
+    start_at: usize,
+    page_size: usize,
+    total: usize,
+) -> impl Iterator<Item = usize> {
+    (start_at + page_size..total).step_by(page_size)
 }
 
 const BASE64TABLE: [u8; 64] = [
@@ -243,7 +1078,7 @@ fn basic_authentication_header(user: &str, token: &str) -> Box<str> {
     }
 
     // Remaining, if it exists
-    if input_length % 3 != 0 {
+    if !input_length.is_multiple_of(3) {
         let mut n = 0;
         let mut index = 0;
         for byte in iterator {
@@ -266,7 +1101,10 @@ fn basic_authentication_header(user: &str, token: &str) -> Box<str> {
 
 #[cfg(test)]
 mod test {
-    use super::basic_authentication_header;
+    use super::{
+        ChangeAuthors, Changelog, IssueType, Page, basic_authentication_header,
+        remaining_page_offsets,
+    };
 
     #[test]
     fn encode_test() {
@@ -285,4 +1123,50 @@ mod test {
         let result = basic_authentication_header("user", "$7r4n/ge$741ng");
         assert_eq!(result.as_ref(), "Basic dXNlcjokN3I0bi9nZSQ3NDFuZw==")
     }
+
+    #[test]
+    fn page_offsets() {
+        let offsets: Vec<_> = remaining_page_offsets(0, 50, 420).collect();
+        assert_eq!(offsets, [50, 100, 150, 200, 250, 300, 350, 400])
+    }
+
+    #[test]
+    fn page_offsets_single_page() {
+        assert_eq!(remaining_page_offsets(0, 50, 42).count(), 0)
+    }
+
+    #[test]
+    fn create_issue_types_page() {
+        let page: Page<IssueType> = serde_json::from_str(
+            r#"{"maxResults": 50, "startAt": 0, "total": 1, "issueTypes": [{"id": "10001", "name": "Bug"}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(page.total, Some(1));
+        assert_eq!(page.values[0].name, "Bug");
+    }
+
+    #[test]
+    fn change_authors_from_the_latest_histories() {
+        let changelog: Changelog = serde_json::from_str(
+            r#"{"histories": [
+                {"author": {"displayName": "Grace Hopper"}, "created": "2024-05-02T10:00:00.000+0000",
+                 "items": [{"field": "status"}]},
+                {"author": {"displayName": "Ada Lovelace"}, "created": "2024-05-01T10:00:00.000+0000",
+                 "items": [{"field": "status"}, {"field": "assignee"}]},
+                {"author": {"displayName": "Alan Turing"}, "created": "2024-05-03T10:00:00.000+0000",
+                 "items": [{"field": "labels"}]}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ChangeAuthors::from(changelog),
+            ChangeAuthors {
+                status: Some("Grace Hopper".into()),
+                assignee: Some("Ada Lovelace".into()),
+                summary: None,
+            }
+        );
+    }
 }
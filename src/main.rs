@@ -1,60 +1,145 @@
 mod app;
 mod config;
+mod editor;
+mod filter;
 mod jira;
+mod markup;
 pub mod tui;
+mod unicode;
 
-use std::sync::mpsc;
+use std::sync::{Arc, mpsc};
+use std::time::{Duration, Instant};
 
-use app::{App, State, Window};
+use app::{App, LogLevel, State, Window};
 use config::Config;
-use jira::Jira;
-use tui::Terminal;
+use jira::{IssueChange, Jira, JiraError};
+use tui::{InputEvent, Key, KeyEvent, MouseEvent, MouseKind, ResizeSignal, Scroll, Terminal};
 
-const CTRL_C: u8 = 3;
+const CTRL_A: KeyEvent = KeyEvent::ctrl('a');
+const CTRL_C: KeyEvent = KeyEvent::ctrl('c');
+const CTRL_D: KeyEvent = KeyEvent::ctrl('d');
+const CTRL_U: KeyEvent = KeyEvent::ctrl('u');
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Only the issues updated since the last sync are fetched in between, but they miss deleted
+/// issues and changes to the sprints.
+const INCREMENTAL_SYNCS_PER_FULL_SYNC: u32 = 10;
 
 enum Event {
-    State(State),
-    Input(u8),
+    /// The state of the board with the given id
+    State(Box<str>, Result<State, JiraError>),
+    /// The issues of the board with the given id that changed since the last sync
+    Changes(Box<str>, Result<Vec<IssueChange>, JiraError>),
+    Input(KeyEvent),
+    Mouse(MouseEvent),
+    Resize,
 }
 
 fn main() {
     let Config {
         user,
         token,
-        board_id,
+        board_ids,
         host,
+        queries,
     } = config::configuration().unwrap();
 
+    // It must happen before any other thread is spawned
+    let resize_signal = ResizeSignal::install().unwrap();
+
     let terminal = Terminal::try_new().unwrap();
     let mut inputs = terminal.tty().unwrap();
-    let jira = Jira::new(&user, &token, host);
+    let jira = Arc::new(Jira::new(&user, &token, host));
 
-    let initial_state = match App::load_state() {
-        Some(state) => state,
-        None => State::new(&jira, &board_id),
-    };
+    // Without a cached state we start empty, the background sync fills it as soon as it can
+    let initial_state = App::load_state(&board_ids[0])
+        .or_else(App::load_legacy_state)
+        .unwrap_or_default();
 
-    let mut ui = App::new(terminal, initial_state);
+    let mut board_id = board_ids[0].clone();
+    let (board_sender, board_receiver) = mpsc::channel();
+    let mut ui = App::new(
+        terminal,
+        jira.clone(),
+        board_ids,
+        board_sender,
+        initial_state,
+    );
 
     let (sender, receiver) = mpsc::sync_channel(0);
 
-    // This thread updates the state in the background
+    // This thread updates the state in the background, following the board picked in the UI
     let state_sender = sender.clone();
     std::thread::spawn(move || {
+        // Without a successful full sync there is nothing to apply the changes to
+        let mut last_sync: Option<Instant> = None;
+        let mut incremental_syncs = 0;
+
         loop {
-            let state = State::new(&jira, &board_id);
-            state_sender.send(Event::State(state)).unwrap();
-            std::thread::sleep(std::time::Duration::from_secs(30))
+            let started = Instant::now();
+
+            let synced = match last_sync {
+                Some(last_sync) if incremental_syncs < INCREMENTAL_SYNCS_PER_FULL_SYNC => {
+                    // A minute of margin, so no change falls between two syncs
+                    let minutes = last_sync.elapsed().as_secs().div_ceil(60) + 1;
+                    let changes = jira.get_board_issues_updated_since(&board_id, minutes);
+                    let synced = changes.is_ok();
+                    incremental_syncs += 1;
+
+                    let event = Event::Changes(board_id.clone(), changes);
+                    state_sender.send(event).unwrap();
+                    synced
+                }
+                _ => {
+                    let state = State::new(&jira, &board_id, &queries);
+                    let synced = state.is_ok();
+                    incremental_syncs = 0;
+
+                    state_sender
+                        .send(Event::State(board_id.clone(), state))
+                        .unwrap();
+                    synced
+                }
+            };
+
+            // A failed sync is covered by the next one
+            if synced {
+                last_sync = Some(started);
+            }
+
+            // A full sync is asked for when picking a board, only the last one picked matters
+            match board_receiver.recv_timeout(SYNC_INTERVAL) {
+                Ok(new_board_id) => {
+                    board_id = new_board_id;
+                    last_sync = None;
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => (),
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+            if let Some(new_board_id) = board_receiver.try_iter().last() {
+                board_id = new_board_id;
+            }
         }
     });
 
-    // This thread receive user input in the background
+    // This thread waits for the terminal to be resized in the background
+    let resize_sender = sender.clone();
     std::thread::spawn(move || {
         loop {
-            let Some(input) = inputs.next().map(|input| input.unwrap()) else {
-                break;
+            resize_signal.wait().unwrap();
+            resize_sender.send(Event::Resize).unwrap();
+        }
+    });
+
+    // This thread receive user input in the background
+    std::thread::spawn(move || {
+        while let Some(input) = inputs.next().map(|input| input.unwrap()) {
+            let event = match input {
+                InputEvent::Key(key) => Event::Input(key),
+                InputEvent::Mouse(mouse) => Event::Mouse(mouse),
             };
-            sender.send(Event::Input(input)).unwrap();
+            sender.send(event).unwrap();
         }
     });
 
@@ -62,31 +147,129 @@ fn main() {
         ui.render();
 
         match receiver.recv().unwrap() {
-            Event::State(state) => ui.update_state(state),
+            Event::State(board_id, Ok(state)) => ui.update_state(&board_id, state),
+            Event::Changes(board_id, Ok(changes)) => ui.update_issues(&board_id, changes),
+            Event::Resize => ui.resize(),
+            Event::Mouse(mouse) => match mouse.kind {
+                MouseKind::Press(_) => ui.click(mouse.position),
+                MouseKind::ScrollDown => ui.scroll(mouse.position, true),
+                MouseKind::ScrollUp => ui.scroll(mouse.position, false),
+                MouseKind::Release => (),
+            },
+            Event::State(_, Err(err)) | Event::Changes(_, Err(err)) => {
+                ui.log(LogLevel::Error, format!("Could not sync state: {err}"))
+            }
+            // Popups capture every input until they are closed
+            Event::Input(input) if matches!(ui.active_window, Window::Popup) => {
+                match input.plain() {
+                    Some(Key::Char('j') | Key::Down) => ui.move_popup_selection_down(),
+                    Some(Key::Char('k') | Key::Up) => ui.move_popup_selection_up(),
+                    Some(Key::Enter) => ui.confirm_popup(),
+                    Some(Key::Escape | Key::Char('q')) => ui.close_popup(),
+                    _ => (),
+                }
+            }
+            // Prompts capture every input as text until they are confirmed or cancelled
+            Event::Input(input) if matches!(ui.active_window, Window::Prompt) => {
+                match input.plain() {
+                    Some(Key::Enter) => ui.confirm_prompt(),
+                    Some(Key::Escape) => ui.close_prompt(),
+                    Some(Key::Backspace) => ui.pop_prompt_character(),
+                    Some(Key::Char(c)) => ui.push_prompt_character(c),
+                    _ => (),
+                }
+            }
             Event::Input(input) => {
-                // Commands that are independent to the active_window
+                if input == CTRL_C || input.plain() == Some(Key::Char('q')) {
+                    ui.save_state();
+                    break;
+                }
+
+                // Half a page at a time, like in most pagers
                 match input {
-                    b'1' => ui.select_sprints_window(),
-                    b'2' => ui.select_issues_window(),
-                    b'3' => ui.select_issue_description_window(),
-                    b'q' | CTRL_C => {
-                        ui.save_state();
-                        break;
+                    CTRL_D => ui.scroll_text(Scroll::HalfPageDown),
+                    CTRL_U => ui.scroll_text(Scroll::HalfPageUp),
+                    CTRL_A if matches!(ui.active_window, Window::Issues) => {
+                        ui.toggle_visible_marks()
                     }
                     _ => (),
+                }
+
+                let Some(key) = input.plain() else {
+                    continue;
+                };
+
+                // Commands that are independent to the active_window
+                match key {
+                    Key::Char('1') => ui.select_sprints_window(),
+                    Key::Char('2') => ui.select_issues_window(),
+                    Key::Char('3') => ui.select_issue_description_window(),
+                    Key::Char('4') => ui.select_comments_window(),
+                    Key::Char('b') => ui.toggle_board(),
+                    Key::Char('B') => ui.open_board_prompt(),
+                    Key::Char('r') => ui.sync_board(),
+                    _ => (),
                 };
 
                 // Window-specific commands.
                 match ui.active_window {
-                    Window::Issues => match input {
-                        b'j' => ui.move_issue_selection_down(),
-                        b'k' => ui.move_issue_selection_up(),
-                        // b'/' => ui.select_filtering_window()
+                    Window::Issues => match key {
+                        Key::Char('j') | Key::Down => ui.move_issue_selection_down(),
+                        Key::Char('k') | Key::Up => ui.move_issue_selection_up(),
+                        Key::Char('t') => ui.open_transitions_popup(),
+                        Key::Char('n') => ui.open_create_issue_popup(),
+                        Key::Char('e') => ui.edit_issue(),
+                        Key::Char('a') => ui.open_assignee_popup(),
+                        Key::Char('m') => ui.assign_selected_issue_to_myself(),
+                        Key::Char(' ') => ui.toggle_issue_mark(),
+                        Key::Char('s') => ui.open_move_issues_popup(),
+                        Key::Char('l') => ui.open_label_prompt(),
+                        Key::Char('D') => ui.open_delete_popup(),
+                        Key::Char('K') => ui.rank_issue_up(),
+                        Key::Char('J') => ui.rank_issue_down(),
+                        Key::Char('T') => ui.rank_issue_to_top(),
+                        Key::Char('f') => ui.open_search_prompt(),
+                        Key::Char('/') => ui.open_filter_prompt(),
+                        Key::Escape => ui.clear_filter_or_search(),
                         _ => (),
                     },
-                    Window::Sprints => match input {
-                        b'j' => ui.move_sprint_selection_down(),
-                        b'k' => ui.move_sprint_selection_up(),
+                    Window::Description | Window::Comments => match key {
+                        Key::Char('j') | Key::Down => ui.scroll_text(Scroll::LineDown),
+                        Key::Char('k') | Key::Up => ui.scroll_text(Scroll::LineUp),
+                        Key::PageDown => ui.scroll_text(Scroll::HalfPageDown),
+                        Key::PageUp => ui.scroll_text(Scroll::HalfPageUp),
+                        Key::Char('g') | Key::Home => ui.scroll_text(Scroll::Top),
+                        Key::Char('G') | Key::End => ui.scroll_text(Scroll::Bottom),
+                        Key::Char('c') if matches!(ui.active_window, Window::Comments) => {
+                            ui.open_comment_prompt()
+                        }
+                        _ => (),
+                    },
+                    Window::Board => match key {
+                        Key::Char('h') | Key::Left => ui.move_column_selection(false),
+                        Key::Char('l') | Key::Right => ui.move_column_selection(true),
+                        Key::Char('j') | Key::Down => ui.move_card_selection_down(),
+                        Key::Char('k') | Key::Up => ui.move_card_selection_up(),
+                        Key::Char('H') => ui.move_card(false),
+                        Key::Char('L') => ui.move_card(true),
+                        Key::Char('t') => ui.open_transitions_popup(),
+                        Key::Char('e') => ui.edit_issue(),
+                        Key::Char('a') => ui.open_assignee_popup(),
+                        Key::Char('m') => ui.assign_selected_issue_to_myself(),
+                        Key::Char('/') => ui.open_filter_prompt(),
+                        Key::Escape => ui.clear_filter(),
+                        _ => (),
+                    },
+                    Window::Sprints => match key {
+                        Key::Char('j') | Key::Down => ui.move_sprint_selection_down(),
+                        Key::Char('k') | Key::Up => ui.move_sprint_selection_up(),
+                        Key::Char('n') => ui.create_sprint(),
+                        Key::Char('e') => ui.edit_selected_sprint(),
+                        Key::Char('s') => ui.start_selected_sprint(),
+                        Key::Char('c') => ui.complete_selected_sprint(),
+                        Key::Char('f') => ui.open_search_prompt(),
+                        Key::Char('/') => ui.open_filter_prompt(),
+                        Key::Escape => ui.clear_filter(),
                         _ => (),
                     },
                     _ => (),
@@ -95,6 +278,3 @@ fn main() {
         }
     }
 }
-
-// TODO: Allow filtering issues by who is assigned to it
-// FIXME: Perform better error handling instead of unwrapping everything.
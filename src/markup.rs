@@ -0,0 +1,764 @@
+//! Rich text used by Jira's descriptions and comments.
+//! Jira speaks two formats: the wiki markup (API v2) and the Atlassian Document Format, or ADF,
+//! (API v3). Both are parsed into the same `Document`, which is then laid out as styled text.
+use serde_json::Value;
+
+use crate::tui::{Attributes, Color, Span, Style};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Document {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Heading {
+        level: u8,
+        content: Vec<Inline>,
+    },
+    Paragraph(Vec<Inline>),
+    /// Every item is made of blocks, so lists can be nested
+    List {
+        ordered: bool,
+        items: Vec<Vec<Block>>,
+    },
+    Code(String),
+    Quote(Vec<Block>),
+    Rule,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inline {
+    pub text: String,
+    pub marks: Marks,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Marks {
+    pub strong: bool,
+    pub emphasis: bool,
+    pub strike: bool,
+    pub underline: bool,
+    pub code: bool,
+    pub link: Option<String>,
+}
+
+impl Inline {
+    fn plain(text: impl Into<String>) -> Inline {
+        Inline {
+            text: text.into(),
+            marks: Marks::default(),
+        }
+    }
+}
+
+impl Document {
+    /// Lays out the document as styled text, ready to be shown by `tui::Text`.
+    pub fn spans(&self) -> Vec<Span> {
+        let mut spans = Vec::new();
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            // Top level blocks are separated by an empty line
+            if index > 0 {
+                spans.push(Span::new("\n", Style::default()));
+            }
+
+            write_block(block, "", "", &mut spans);
+        }
+
+        spans
+    }
+
+    /// Writes the document back as wiki markup, which is what API v2 expects when editing.
+    pub fn to_wiki(&self) -> String {
+        let mut wiki = String::new();
+
+        for (index, block) in self.blocks.iter().enumerate() {
+            if index > 0 {
+                wiki.push_str("\n\n");
+            }
+
+            write_wiki_block(block, "", &mut wiki);
+        }
+
+        wiki
+    }
+}
+
+/// Writes a block whose first line starts with `first_prefix` and the others with `prefix`.
+fn write_block(block: &Block, first_prefix: &str, prefix: &str, spans: &mut Vec<Span>) {
+    match block {
+        Block::Heading { content, .. } => {
+            let style = Style {
+                foreground_color: Some(Color::Cyan),
+                attributes: Attributes {
+                    bold: true,
+                    ..Default::default()
+                },
+            };
+
+            spans.push(Span::new(first_prefix, Style::default()));
+            write_inlines(content, prefix, style, spans);
+            spans.push(Span::new("\n", Style::default()));
+        }
+        Block::Paragraph(content) => {
+            spans.push(Span::new(first_prefix, Style::default()));
+            write_inlines(content, prefix, Style::default(), spans);
+            spans.push(Span::new("\n", Style::default()));
+        }
+        Block::List { ordered, items } => {
+            for (index, item) in items.iter().enumerate() {
+                let marker = match ordered {
+                    true => format!("{}. ", index + 1),
+                    false => "• ".into(),
+                };
+
+                let item_first_prefix = match index {
+                    0 => format!("{first_prefix}{marker}"),
+                    _ => format!("{prefix}{marker}"),
+                };
+                let item_prefix = format!("{prefix}{}", " ".repeat(marker.chars().count()));
+
+                for (index, block) in item.iter().enumerate() {
+                    let block_first_prefix = match index {
+                        0 => &item_first_prefix,
+                        _ => &item_prefix,
+                    };
+
+                    write_block(block, block_first_prefix, &item_prefix, spans);
+                }
+            }
+        }
+        Block::Code(code) => {
+            let style = Style {
+                foreground_color: Some(Color::Yellow),
+                ..Default::default()
+            };
+
+            for (index, line) in code.lines().enumerate() {
+                let line_prefix = if index == 0 { first_prefix } else { prefix };
+
+                spans.push(Span::new(format!("{line_prefix}  "), Style::default()));
+                spans.push(Span::new(line, style));
+                spans.push(Span::new("\n", Style::default()));
+            }
+        }
+        Block::Quote(blocks) => {
+            for (index, block) in blocks.iter().enumerate() {
+                let block_first_prefix = if index == 0 { first_prefix } else { prefix };
+
+                write_block(
+                    block,
+                    &format!("{block_first_prefix}│ "),
+                    &format!("{prefix}│ "),
+                    spans,
+                );
+            }
+        }
+        Block::Rule => {
+            spans.push(Span::new(
+                format!("{first_prefix}{}\n", "─".repeat(20)),
+                Style::default(),
+            ));
+        }
+    }
+}
+
+/// Writes the text, breaking lines are followed by the `prefix` to keep the indentation.
+fn write_inlines(inlines: &[Inline], prefix: &str, base_style: Style, spans: &mut Vec<Span>) {
+    for inline in inlines {
+        let mut style = base_style;
+        style.attributes.bold |= inline.marks.strong;
+        style.attributes.italic |= inline.marks.emphasis;
+        style.attributes.strikethrough |= inline.marks.strike;
+        style.attributes.underline |= inline.marks.underline || inline.marks.link.is_some();
+
+        if inline.marks.code {
+            style.foreground_color = Some(Color::Yellow);
+        } else if inline.marks.link.is_some() {
+            style.foreground_color = Some(Color::Blue);
+        }
+
+        for (index, line) in inline.text.split('\n').enumerate() {
+            if index > 0 {
+                spans.push(Span::new(format!("\n{prefix}"), Style::default()));
+            }
+
+            spans.push(Span::new(line, style));
+        }
+    }
+}
+
+fn write_wiki_block(block: &Block, list_marker: &str, wiki: &mut String) {
+    match block {
+        Block::Heading { level, content } => {
+            wiki.push_str(&format!("h{level}. "));
+            write_wiki_inlines(content, wiki);
+        }
+        Block::Paragraph(content) => write_wiki_inlines(content, wiki),
+        Block::List { ordered, items } => {
+            let marker = format!("{list_marker}{}", if *ordered { '#' } else { '*' });
+
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    wiki.push('\n');
+                }
+
+                wiki.push_str(&marker);
+                wiki.push(' ');
+
+                for (index, block) in item.iter().enumerate() {
+                    match block {
+                        Block::List { .. } => {
+                            wiki.push('\n');
+                            write_wiki_block(block, &marker, wiki);
+                        }
+                        _ => {
+                            if index > 0 {
+                                wiki.push(' ');
+                            }
+                            write_wiki_block(block, &marker, wiki)
+                        }
+                    }
+                }
+            }
+        }
+        Block::Code(code) => wiki.push_str(&format!("{{code}}\n{code}\n{{code}}")),
+        Block::Quote(blocks) => {
+            wiki.push_str("{quote}\n");
+            for block in blocks {
+                write_wiki_block(block, "", wiki);
+                wiki.push('\n');
+            }
+            wiki.push_str("{quote}");
+        }
+        Block::Rule => wiki.push_str("----"),
+    }
+}
+
+fn write_wiki_inlines(inlines: &[Inline], wiki: &mut String) {
+    for inline in inlines {
+        let marks = &inline.marks;
+        let mut text = inline.text.clone();
+
+        if marks.code {
+            text = format!("{{{{{text}}}}}");
+        }
+        if marks.strong {
+            text = format!("*{text}*");
+        }
+        if marks.emphasis {
+            text = format!("_{text}_");
+        }
+        if marks.strike {
+            text = format!("-{text}-");
+        }
+        if marks.underline {
+            text = format!("+{text}+");
+        }
+        if let Some(link) = &marks.link {
+            text = match text == *link {
+                true => format!("[{link}]"),
+                false => format!("[{text}|{link}]"),
+            };
+        }
+
+        wiki.push_str(&text);
+    }
+}
+
+/// Parses Jira's wiki markup, anything we don't understand is kept as plain text.
+pub fn parse_wiki(text: &str) -> Document {
+    let mut blocks = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Vec<ListLine> = Vec::new();
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        let is_list_line = parse_list_line(trimmed).is_some();
+        if !is_list_line && !list.is_empty() {
+            blocks.push(build_list(&list, 1));
+            list.clear();
+        }
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut paragraph, &mut blocks);
+        } else if let Some(list_line) = parse_list_line(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            list.push(list_line);
+        } else if let Some(tag) = ["{code", "{noformat"]
+            .into_iter()
+            .find(|tag| trimmed.starts_with(tag))
+        {
+            flush_paragraph(&mut paragraph, &mut blocks);
+
+            let closing_tag = format!("{}}}", tag);
+            // Whatever comes after the opening tag (e.g. '{code:java}') is already code
+            let first_line = trimmed.split_once('}').map_or("", |(_, rest)| rest);
+
+            let mut code = Vec::new();
+            match first_line.split_once(&closing_tag) {
+                Some((content, _)) => code.push(content),
+                None => {
+                    if !first_line.is_empty() {
+                        code.push(first_line);
+                    }
+
+                    for line in lines.by_ref() {
+                        if let Some((content, _)) = line.split_once(&closing_tag) {
+                            if !content.is_empty() {
+                                code.push(content);
+                            }
+                            break;
+                        }
+                        code.push(line);
+                    }
+                }
+            }
+
+            blocks.push(Block::Code(code.join("\n")));
+        } else if let Some(first_line) = trimmed.strip_prefix("{quote}") {
+            flush_paragraph(&mut paragraph, &mut blocks);
+
+            let mut quote = Vec::new();
+            match first_line.split_once("{quote}") {
+                Some((content, _)) => quote.push(content),
+                None => {
+                    quote.push(first_line);
+
+                    for line in lines.by_ref() {
+                        if let Some((content, _)) = line.split_once("{quote}") {
+                            quote.push(content);
+                            break;
+                        }
+                        quote.push(line);
+                    }
+                }
+            }
+
+            blocks.push(Block::Quote(parse_wiki(&quote.join("\n")).blocks));
+        } else if let Some(content) = trimmed.strip_prefix("bq. ") {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(Block::Quote(vec![Block::Paragraph(parse_wiki_inlines(
+                content,
+            ))]));
+        } else if let Some((level, content)) = parse_heading(trimmed) {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(Block::Heading {
+                level,
+                content: parse_wiki_inlines(content),
+            });
+        } else if trimmed.len() >= 4 && trimmed.chars().all(|c| c == '-') {
+            flush_paragraph(&mut paragraph, &mut blocks);
+            blocks.push(Block::Rule);
+        } else {
+            paragraph.push(line);
+        }
+    }
+
+    if !list.is_empty() {
+        blocks.push(build_list(&list, 1));
+    }
+    flush_paragraph(&mut paragraph, &mut blocks);
+
+    Document { blocks }
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, blocks: &mut Vec<Block>) {
+    if paragraph.is_empty() {
+        return;
+    }
+
+    blocks.push(Block::Paragraph(parse_wiki_inlines(&paragraph.join("\n"))));
+    paragraph.clear();
+}
+
+/// 'h1. Title' up to 'h6. Title'
+fn parse_heading(line: &str) -> Option<(u8, &str)> {
+    let rest = line.strip_prefix('h')?;
+    let level = rest.chars().next()?.to_digit(10)?;
+    let content = rest[1..].strip_prefix(". ")?;
+
+    (1..=6).contains(&level).then_some((level as u8, content))
+}
+
+struct ListLine {
+    depth: usize,
+    ordered: bool,
+    content: Vec<Inline>,
+}
+
+/// '* item', '** nested item', '# ordered item' or '- item'
+fn parse_list_line(line: &str) -> Option<ListLine> {
+    let marker_length = line.chars().take_while(|c| *c == '*' || *c == '#').count();
+
+    let (marker, content) = match marker_length {
+        0 => ("-", line.strip_prefix("- ")?),
+        _ => (
+            &line[..marker_length],
+            line[marker_length..].strip_prefix(' ')?,
+        ),
+    };
+
+    Some(ListLine {
+        depth: marker.len(),
+        ordered: marker.ends_with('#'),
+        content: parse_wiki_inlines(content.trim()),
+    })
+}
+
+/// Turns consecutive list lines into (possibly nested) lists, starting at `depth`.
+fn build_list(lines: &[ListLine], depth: usize) -> Block {
+    let ordered = lines.first().is_some_and(|line| line.ordered);
+    let mut items: Vec<Vec<Block>> = Vec::new();
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = &lines[index];
+
+        if line.depth <= depth || items.is_empty() {
+            items.push(vec![Block::Paragraph(line.content.clone())]);
+            index += 1;
+            continue;
+        }
+
+        // Deeper lines belong to a list nested in the previous item
+        let end = lines[index..]
+            .iter()
+            .position(|line| line.depth <= depth)
+            .map_or(lines.len(), |position| index + position);
+
+        if let Some(item) = items.last_mut() {
+            item.push(build_list(&lines[index..end], depth + 1));
+        }
+        index = end;
+    }
+
+    Block::List { ordered, items }
+}
+
+/// Parses '*strong*', '_emphasis_', '-strike-', '+underline+', '{{code}}' and '[text|link]'.
+fn parse_wiki_inlines(text: &str) -> Vec<Inline> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut inlines = Vec::new();
+    let mut marks = Marks::default();
+    let mut current = String::new();
+    let mut index = 0;
+
+    let flush = |current: &mut String, marks: &Marks, inlines: &mut Vec<Inline>| {
+        if !current.is_empty() {
+            inlines.push(Inline {
+                text: std::mem::take(current),
+                marks: marks.clone(),
+            });
+        }
+    };
+
+    while index < chars.len() {
+        let c = chars[index];
+
+        if c == '{'
+            && chars.get(index + 1) == Some(&'{')
+            && let Some(end) = find_sequence(&chars, index + 2, &['}', '}'])
+        {
+            flush(&mut current, &marks, &mut inlines);
+
+            let mut code_marks = marks.clone();
+            code_marks.code = true;
+            inlines.push(Inline {
+                text: chars[index + 2..end].iter().collect(),
+                marks: code_marks,
+            });
+
+            index = end + 2;
+            continue;
+        }
+
+        if c == '['
+            && let Some(end) = find_sequence(&chars, index + 1, &[']'])
+        {
+            let content: String = chars[index + 1..end].iter().collect();
+            let (text, link) = match content.split_once('|') {
+                Some((text, link)) => (text.to_string(), link.to_string()),
+                None => (content.clone(), content),
+            };
+
+            flush(&mut current, &marks, &mut inlines);
+
+            let mut link_marks = marks.clone();
+            link_marks.link = Some(link);
+            inlines.push(Inline {
+                text,
+                marks: link_marks,
+            });
+
+            index = end + 1;
+            continue;
+        }
+
+        if c == '\\' && chars.get(index + 1) == Some(&'\\') {
+            current.push('\n');
+            index += 2;
+            continue;
+        }
+
+        if let Some(active) = mark_for(&mut marks, c).map(|active| *active) {
+            let previous = index.checked_sub(1).map(|index| chars[index]);
+            let next = chars.get(index + 1).copied();
+
+            let closes = active
+                && previous.is_some_and(|c| !c.is_whitespace())
+                && next.is_none_or(is_boundary);
+            let opens = !active
+                && previous.is_none_or(is_boundary)
+                && next.is_some_and(|c| !c.is_whitespace())
+                && find_closing_mark(&chars, index + 1, c).is_some();
+
+            if closes || opens {
+                // The text before the mark keeps the old marks
+                flush(&mut current, &marks, &mut inlines);
+
+                if let Some(mark) = mark_for(&mut marks, c) {
+                    *mark = !active;
+                }
+
+                index += 1;
+                continue;
+            }
+        }
+
+        current.push(c);
+        index += 1;
+    }
+
+    flush(&mut current, &marks, &mut inlines);
+
+    inlines
+}
+
+fn mark_for(marks: &mut Marks, c: char) -> Option<&mut bool> {
+    match c {
+        '*' => Some(&mut marks.strong),
+        '_' => Some(&mut marks.emphasis),
+        '-' => Some(&mut marks.strike),
+        '+' => Some(&mut marks.underline),
+        _ => None,
+    }
+}
+
+fn is_boundary(c: char) -> bool {
+    !c.is_alphanumeric()
+}
+
+/// Where the `sequence` starts, as long as it's in the same line.
+fn find_sequence(chars: &[char], start: usize, sequence: &[char]) -> Option<usize> {
+    (start..chars.len())
+        .take_while(|index| chars[*index] != '\n')
+        .find(|index| chars[*index..].starts_with(sequence))
+}
+
+fn find_closing_mark(chars: &[char], start: usize, mark: char) -> Option<usize> {
+    (start + 1..chars.len())
+        .take_while(|index| chars[*index] != '\n')
+        .find(|index| {
+            chars[*index] == mark
+                && !chars[index - 1].is_whitespace()
+                && chars.get(index + 1).copied().is_none_or(is_boundary)
+        })
+}
+
+/// Parses the Atlassian Document Format, unknown nodes are skipped but their content is kept.
+pub fn parse_adf(node: &Value) -> Document {
+    Document {
+        blocks: adf_blocks(node),
+    }
+}
+
+fn adf_blocks(node: &Value) -> Vec<Block> {
+    adf_content(node).flat_map(adf_block).collect()
+}
+
+fn adf_content(node: &Value) -> impl Iterator<Item = &Value> {
+    node["content"].as_array().into_iter().flatten()
+}
+
+fn adf_block(node: &Value) -> Vec<Block> {
+    let block = match node["type"].as_str().unwrap_or_default() {
+        "paragraph" => Block::Paragraph(adf_inlines(node)),
+        "heading" => Block::Heading {
+            level: node["attrs"]["level"].as_u64().unwrap_or(1) as u8,
+            content: adf_inlines(node),
+        },
+        kind @ ("bulletList" | "orderedList") => Block::List {
+            ordered: kind == "orderedList",
+            items: adf_content(node).map(adf_blocks).collect(),
+        },
+        "codeBlock" => Block::Code(
+            adf_content(node)
+                .filter_map(|node| node["text"].as_str())
+                .collect(),
+        ),
+        "blockquote" => Block::Quote(adf_blocks(node)),
+        "rule" => Block::Rule,
+        "mediaSingle" | "mediaGroup" => Block::Paragraph(vec![Inline::plain("[attachment]")]),
+        // Containers (e.g. panels, tables or layouts) are flattened into their content
+        _ => return adf_blocks(node),
+    };
+
+    vec![block]
+}
+
+fn adf_inlines(node: &Value) -> Vec<Inline> {
+    adf_content(node)
+        .filter_map(|node| {
+            let attribute = |name: &str| node["attrs"][name].as_str().map(String::from);
+
+            match node["type"].as_str().unwrap_or_default() {
+                "text" => Some(Inline {
+                    text: node["text"].as_str().unwrap_or_default().into(),
+                    marks: adf_marks(node),
+                }),
+                "hardBreak" => Some(Inline::plain("\n")),
+                "mention" | "status" | "emoji" => attribute("text")
+                    .or(attribute("shortName"))
+                    .map(Inline::plain),
+                "inlineCard" => attribute("url").map(|url| Inline {
+                    text: url.clone(),
+                    marks: Marks {
+                        link: Some(url),
+                        ..Default::default()
+                    },
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn adf_marks(node: &Value) -> Marks {
+    let mut marks = Marks::default();
+
+    for mark in node["marks"].as_array().into_iter().flatten() {
+        match mark["type"].as_str().unwrap_or_default() {
+            "strong" => marks.strong = true,
+            "em" => marks.emphasis = true,
+            "strike" => marks.strike = true,
+            "underline" => marks.underline = true,
+            "code" => marks.code = true,
+            "link" => marks.link = mark["attrs"]["href"].as_str().map(String::from),
+            _ => (),
+        }
+    }
+
+    marks
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Block, Inline, Marks, parse_adf, parse_wiki};
+
+    #[test]
+    fn wiki_blocks() {
+        let document = parse_wiki("h2. Title\n\nSome text\n* one\n** nested\n* two\n----");
+
+        assert_eq!(document.blocks.len(), 4);
+        assert!(matches!(
+            document.blocks[0],
+            Block::Heading { level: 2, .. }
+        ));
+        assert!(matches!(document.blocks[1], Block::Paragraph(_)));
+        assert!(matches!(document.blocks[3], Block::Rule));
+
+        let Block::List { ordered, items } = &document.blocks[2] else {
+            panic!("expected a list, got {:?}", document.blocks[2]);
+        };
+        assert!(!ordered);
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[0][1], Block::List { .. }));
+    }
+
+    #[test]
+    fn wiki_code_block() {
+        let document = parse_wiki("{code:rust}\nfn main() {}\n{code}\nafter");
+
+        assert_eq!(
+            document.blocks[0],
+            Block::Code("fn main() {}".into()),
+            "{:?}",
+            document.blocks
+        );
+        assert_eq!(document.blocks.len(), 2);
+    }
+
+    #[test]
+    fn wiki_single_line_quote() {
+        let document = parse_wiki("{quote}quoted{quote}\nafter");
+
+        assert_eq!(document.blocks.len(), 2, "{:?}", document.blocks);
+        let Block::Quote(quote) = &document.blocks[0] else {
+            panic!("expected a quote, got {:?}", document.blocks[0]);
+        };
+        assert_eq!(quote.len(), 1);
+        assert!(matches!(document.blocks[1], Block::Paragraph(_)));
+    }
+
+    #[test]
+    fn wiki_inlines() {
+        let document = parse_wiki("a *bold* word, 2-3 - x and [docs|https://example.com]");
+
+        let Block::Paragraph(inlines) = &document.blocks[0] else {
+            panic!("expected a paragraph");
+        };
+
+        let bold = inlines.iter().find(|inline| inline.marks.strong).unwrap();
+        assert_eq!(bold.text, "bold");
+        assert!(inlines.iter().all(|inline| !inline.marks.strike));
+
+        let link = inlines.last().unwrap();
+        assert_eq!(link.text, "docs");
+        assert_eq!(link.marks.link.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn adf_document() {
+        let adf = serde_json::json!({
+            "type": "doc",
+            "content": [
+                { "type": "heading", "attrs": { "level": 1 }, "content": [{ "type": "text", "text": "Title" }] },
+                { "type": "paragraph", "content": [
+                    { "type": "text", "text": "bold", "marks": [{ "type": "strong" }] },
+                    { "type": "hardBreak" },
+                ]},
+                { "type": "bulletList", "content": [
+                    { "type": "listItem", "content": [{ "type": "paragraph", "content": [{ "type": "text", "text": "item" }] }] },
+                ]},
+            ]
+        });
+
+        let document = parse_adf(&adf);
+
+        assert_eq!(document.blocks.len(), 3);
+        assert_eq!(
+            document.blocks[1],
+            Block::Paragraph(vec![
+                Inline {
+                    text: "bold".into(),
+                    marks: Marks {
+                        strong: true,
+                        ..Default::default()
+                    }
+                },
+                Inline::plain("\n"),
+            ])
+        );
+    }
+
+    #[test]
+    fn wiki_round_trip() {
+        let wiki = "h1. Title\n\n* one\n** nested\n\n*bold* and {{code}}";
+
+        assert_eq!(parse_wiki(wiki).to_wiki(), wiki);
+    }
+}
@@ -3,10 +3,15 @@
 //! covered and it just splits it between different widgets.
 use std::io::{Read, Write, stdout};
 use std::ops::{Add, AddAssign};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 use std::{mem::MaybeUninit, os::fd::AsRawFd};
 
 use libc::termios as Termios;
 
+use crate::unicode;
+
 pub trait Widget: CommonWidget {
     fn render(&self, buffer: &mut Buffer);
 }
@@ -20,6 +25,12 @@ pub trait CommonWidget {
     fn set_border(&mut self, color: Option<Color>);
     fn set_title(&mut self, title: Option<String>);
 
+    /// Whether the `position` of the screen falls inside the widget.
+    fn contains(&self, position: Vector2) -> bool;
+
+    /// A floating region over the bottom `height` rows of the widget.
+    fn overlay(&self, height: usize) -> RenderingRegion;
+
     fn rendering_region(self) -> RenderingRegion;
 }
 
@@ -56,6 +67,14 @@ macro_rules! implement_common_widget {
                 self.rendering_region.usable_size()
             }
 
+            fn contains(&self, position: $crate::tui::Vector2) -> bool {
+                self.rendering_region.contains(position)
+            }
+
+            fn overlay(&self, height: usize) -> $crate::tui::RenderingRegion {
+                self.rendering_region.overlay(height)
+            }
+
             fn rendering_region(self) -> $crate::tui::RenderingRegion {
                 self.rendering_region
             }
@@ -63,7 +82,7 @@ macro_rules! implement_common_widget {
     };
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Vector2 {
     x: usize,
     y: usize,
@@ -113,12 +132,41 @@ impl Buffer {
 
         &mut self.data[self.size.width * position.y + position.x]
     }
+
+    /// Widgets drawn over others (e.g. popups) can leave half of a wide grapheme behind, which
+    /// would overflow into the next cell or leave garbage in it, so they are blanked.
+    fn remove_broken_wide_graphemes(&mut self) {
+        if self.size.width == 0 {
+            return;
+        }
+
+        for row in self.data.chunks_mut(self.size.width) {
+            for x in 0..row.len() {
+                let broken = match row[x].grapheme.width() {
+                    2 => !row
+                        .get(x + 1)
+                        .is_some_and(|next| next.grapheme.is_continuation()),
+                    0 => x == 0 || row[x - 1].grapheme.width() != 2,
+                    _ => false,
+                };
+
+                if broken {
+                    row[x].grapheme = Grapheme::from(' ');
+                }
+            }
+        }
+    }
 }
 
 pub struct Terminal {
     pub buffer: Buffer,
+    /// What is currently on the screen, only the cells that differ from it are drawn
+    previous_buffer: Buffer,
+    /// The screen content is unknown (e.g. after a resize), so the whole buffer must be drawn
+    invalidated: bool,
     tty: std::fs::File,
     termios: Termios,
+    suspended: Arc<AtomicBool>,
 }
 
 impl Drop for Terminal {
@@ -129,6 +177,7 @@ impl Drop for Terminal {
             )
         };
 
+        Terminal::disable_mouse_reporting();
         Terminal::leave_alternate_screen();
         Terminal::make_cursor_visible();
     }
@@ -143,14 +192,18 @@ impl Terminal {
 
         let terminal = Terminal {
             buffer: Buffer::new(size),
+            previous_buffer: Buffer::new(size),
+            invalidated: true,
             tty,
             termios,
+            suspended: Arc::new(AtomicBool::new(false)),
         };
 
         terminal.enable_raw_mode()?;
 
         Terminal::enter_alternate_screen();
         Terminal::make_cursor_invisible();
+        Terminal::enable_mouse_reporting();
 
         Ok(terminal)
     }
@@ -192,31 +245,100 @@ impl Terminal {
         Ok(())
     }
 
+    /// Gives the terminal back to the user in canonical mode so another program (e.g. an editor)
+    /// can use it, the input stops being read until `resume` is called.
+    pub fn suspend(&mut self) -> std::io::Result<()> {
+        self.suspended.store(true, Ordering::Release);
+
+        Terminal::disable_mouse_reporting();
+        Terminal::leave_alternate_screen();
+        Terminal::make_cursor_visible();
+        stdout().flush()?;
+
+        self.disable_raw_mode()
+    }
+
+    pub fn resume(&mut self) -> std::io::Result<()> {
+        self.enable_raw_mode()?;
+
+        Terminal::enter_alternate_screen();
+        Terminal::make_cursor_invisible();
+        Terminal::enable_mouse_reporting();
+        stdout().flush()?;
+
+        // Whatever ran in the meantime might have drawn over the screen
+        self.invalidated = true;
+        self.suspended.store(false, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Draws the cells that changed since the last frame, in a single write.
     pub fn draw(&mut self) {
-        Terminal::move_cursor_to_home_position();
+        self.buffer.remove_broken_wide_graphemes();
+
+        let mut output = String::new();
+
+        // What the terminal is currently using, unknown until the first cell is written
+        let mut cursor = None;
+        let mut current_foreground_color = None;
+        let mut current_background_color = None;
+        let mut current_attributes = None;
+
+        let width = self.buffer.size.width;
+        let cells = self.buffer.data.iter().zip(&self.previous_buffer.data);
+
+        for (index, (cell, previous_cell)) in cells.enumerate() {
+            // The right half of a wide grapheme is drawn along with the left one
+            if cell.grapheme.is_continuation() || (!self.invalidated && cell == previous_cell) {
+                continue;
+            }
+
+            if cursor != Some(index) {
+                Terminal::move_cursor(&mut output, index % width, index / width);
+            }
 
-        // We always start with the Default color to ensure consistency
-        let mut current_foreground_color = Color::Default;
-        let mut current_background_color = Color::Default;
-        current_foreground_color.apply_foreground();
-        current_background_color.apply_background();
+            if current_foreground_color != Some(cell.foreground_color) {
+                current_foreground_color = Some(cell.foreground_color);
+                cell.foreground_color.apply_foreground(&mut output);
+            }
 
-        for cell in &self.buffer.data {
-            if cell.foreground_color != current_foreground_color {
-                current_foreground_color = cell.foreground_color;
-                current_foreground_color.apply_foreground();
+            if current_background_color != Some(cell.background_color) {
+                current_background_color = Some(cell.background_color);
+                cell.background_color.apply_background(&mut output);
             }
 
-            if cell.background_color != current_background_color {
-                current_background_color = cell.background_color;
-                current_background_color.apply_background();
+            if current_attributes != Some(cell.attributes) {
+                current_attributes = Some(cell.attributes);
+                cell.attributes.apply(&mut output);
             }
 
-            print!("{}", cell.character)
+            output.push_str(cell.grapheme.as_str());
+
+            // Terminals differ on where the cursor ends up after the last column, so we move it
+            cursor = Some(index + cell.grapheme.width()).filter(|next| next % width != 0);
         }
 
-        stdout().flush().unwrap();
-        self.buffer.data.fill(Cell::default())
+        if !output.is_empty() {
+            let mut stdout = stdout().lock();
+            stdout.write_all(output.as_bytes()).unwrap();
+            stdout.flush().unwrap();
+        }
+
+        std::mem::swap(&mut self.buffer, &mut self.previous_buffer);
+        self.buffer.data.fill(Cell::default());
+        self.invalidated = false;
+    }
+
+    /// Reallocates the buffer to the new terminal size, everything must be rendered again.
+    pub fn resize(&mut self) -> std::io::Result<()> {
+        let size = Terminal::size()?;
+        self.buffer = Buffer::new(size);
+        self.previous_buffer = Buffer::new(size);
+        self.invalidated = true;
+        Terminal::clear_screen();
+
+        Ok(())
     }
 
     pub fn rendering_region(&self) -> RenderingRegion {
@@ -255,8 +377,22 @@ impl Terminal {
         print!("\x1b[?1049l");
     }
 
-    fn move_cursor_to_home_position() {
-        print!("\x1B[H");
+    /// Clicks and the wheel are reported with the SGR encoding, which has no coordinate limits.
+    fn enable_mouse_reporting() {
+        print!("\x1b[?1000h\x1b[?1006h");
+    }
+
+    fn disable_mouse_reporting() {
+        print!("\x1b[?1006l\x1b[?1000l");
+    }
+
+    fn clear_screen() {
+        print!("\x1b[2J");
+    }
+
+    /// The escape sequence is 1-based, the position is not.
+    fn move_cursor(output: &mut String, x: usize, y: usize) {
+        output.push_str(&format!("\x1b[{};{}H", y + 1, x + 1));
     }
 
     fn make_cursor_invisible() {
@@ -267,8 +403,476 @@ impl Terminal {
         print!("\x1b[?25h");
     }
 
-    pub fn tty(&self) -> std::io::Result<std::io::Bytes<std::fs::File>> {
-        self.tty.try_clone().map(|file| file.bytes())
+    pub fn tty(&self) -> std::io::Result<TtyInput> {
+        Ok(TtyInput {
+            tty: self.tty.try_clone()?,
+            suspended: self.suspended.clone(),
+            pending: Vec::new(),
+        })
+    }
+}
+
+/// Tells when the terminal was resized (i.e. SIGWINCH was received).
+///
+/// Instead of a signal handler, the signal is blocked and then waited for synchronously. Signal
+/// masks are inherited, so it must be created before spawning any other thread, otherwise the
+/// signal could be delivered (and ignored) in any of them.
+pub struct ResizeSignal {
+    signals: libc::sigset_t,
+}
+
+impl ResizeSignal {
+    pub fn install() -> std::io::Result<ResizeSignal> {
+        unsafe {
+            let mut signals: MaybeUninit<libc::sigset_t> = MaybeUninit::uninit();
+            libc::sigemptyset(signals.as_mut_ptr());
+            libc::sigaddset(signals.as_mut_ptr(), libc::SIGWINCH);
+            let signals = signals.assume_init();
+
+            let result = libc::pthread_sigmask(libc::SIG_BLOCK, &signals, std::ptr::null_mut());
+            if result != 0 {
+                return Err(std::io::Error::from_raw_os_error(result));
+            }
+
+            Ok(ResizeSignal { signals })
+        }
+    }
+
+    /// Blocks until the terminal is resized.
+    pub fn wait(&self) -> std::io::Result<()> {
+        let mut signal = 0;
+        let result = unsafe { libc::sigwait(&self.signals, &mut signal) };
+
+        if result != 0 {
+            return Err(std::io::Error::from_raw_os_error(result));
+        }
+
+        Ok(())
+    }
+
+    /// Child processes inherit the blocked signal, which must be undone for programs (e.g. an
+    /// editor) that want to handle resizes themselves.
+    pub fn unblock() -> std::io::Result<()> {
+        unsafe {
+            let mut signals: MaybeUninit<libc::sigset_t> = MaybeUninit::uninit();
+            libc::sigemptyset(signals.as_mut_ptr());
+            libc::sigaddset(signals.as_mut_ptr(), libc::SIGWINCH);
+
+            let result =
+                libc::pthread_sigmask(libc::SIG_UNBLOCK, signals.as_ptr(), std::ptr::null_mut());
+            if result != 0 {
+                return Err(std::io::Error::from_raw_os_error(result));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The user input, decoded into keys and mouse events.
+pub struct TtyInput {
+    tty: std::fs::File,
+    suspended: Arc<AtomicBool>,
+    /// Bytes read that don't make a whole key yet
+    pending: Vec<u8>,
+}
+
+impl TtyInput {
+    /// How long we wait for input before checking again if the terminal was suspended
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Escape sequences arrive all at once, when the rest doesn't come in time it was the
+    /// Escape key on its own
+    const ESCAPE_TIMEOUT: Duration = Duration::from_millis(25);
+
+    /// Waits up to `timeout` for the TTY to have something to be read.
+    fn poll(&self, timeout: Duration) -> std::io::Result<bool> {
+        let mut fd = libc::pollfd {
+            fd: self.tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+
+        let result = unsafe { libc::poll(&mut fd, 1, timeout.as_millis() as libc::c_int) };
+
+        if result < 0 {
+            let err = std::io::Error::last_os_error();
+
+            // Being interrupted by a signal is the same as timing out for us
+            return match err.kind() {
+                std::io::ErrorKind::Interrupted => Ok(false),
+                _ => Err(err),
+            };
+        }
+
+        Ok(result > 0)
+    }
+
+    /// Blocks until a byte is read, `None` once the TTY is closed.
+    fn read_byte(&mut self) -> Option<std::io::Result<u8>> {
+        loop {
+            if self.suspended.load(Ordering::Acquire) {
+                std::thread::sleep(TtyInput::POLL_INTERVAL);
+                continue;
+            }
+
+            match self.poll(TtyInput::POLL_INTERVAL) {
+                Ok(true) => (),
+                Ok(false) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+
+            // The terminal might have been suspended while we were waiting
+            if self.suspended.load(Ordering::Acquire) {
+                continue;
+            }
+
+            let mut byte = [0];
+            return match self.tty.read(&mut byte) {
+                Ok(0) => None,
+                Ok(_) => Some(Ok(byte[0])),
+                Err(err) => Some(Err(err)),
+            };
+        }
+    }
+}
+
+impl Iterator for TtyInput {
+    type Item = std::io::Result<InputEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.pending.is_empty() {
+                match self.read_byte()? {
+                    Ok(byte) => self.pending.push(byte),
+                    Err(err) => return Some(Err(err)),
+                }
+            }
+
+            let decoded = match decode(&self.pending, true) {
+                Decoded::Incomplete => match self.poll(TtyInput::ESCAPE_TIMEOUT) {
+                    Ok(true) => {
+                        match self.read_byte()? {
+                            Ok(byte) => self.pending.push(byte),
+                            Err(err) => return Some(Err(err)),
+                        }
+                        continue;
+                    }
+                    Ok(false) => decode(&self.pending, false),
+                    Err(err) => return Some(Err(err)),
+                },
+                decoded => decoded,
+            };
+
+            match decoded {
+                Decoded::Event(event, length) => {
+                    self.pending.drain(..length);
+                    return Some(Ok(event));
+                }
+                Decoded::Ignored(length) => {
+                    self.pending.drain(..length);
+                }
+                Decoded::Incomplete => unreachable!("Input is complete once we stop waiting"),
+            }
+        }
+    }
+}
+
+pub enum InputEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+}
+
+/// A key the user pressed, along with the modifiers held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    pub const fn new(key: Key) -> KeyEvent {
+        KeyEvent {
+            key,
+            modifiers: Modifiers {
+                shift: false,
+                alt: false,
+                ctrl: false,
+            },
+        }
+    }
+
+    pub const fn ctrl(c: char) -> KeyEvent {
+        KeyEvent {
+            key: Key::Char(c),
+            modifiers: Modifiers {
+                shift: false,
+                alt: false,
+                ctrl: true,
+            },
+        }
+    }
+
+    /// The key, unless Ctrl or Alt are held. Shift is already part of the character typed.
+    pub fn plain(&self) -> Option<Key> {
+        (!self.modifiers.ctrl && !self.modifiers.alt).then_some(self.key)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    BackTab,
+    Delete,
+    Down,
+    End,
+    Enter,
+    Escape,
+    F(u8),
+    Home,
+    Insert,
+    Left,
+    PageDown,
+    PageUp,
+    Right,
+    Tab,
+    Up,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub alt: bool,
+    pub ctrl: bool,
+}
+
+impl Modifiers {
+    /// Sequences encode the modifiers as 1 plus a bitmask, e.g. '\x1b[1;5A' is Ctrl+Up.
+    fn from_parameter(parameter: u16) -> Modifiers {
+        let mask = parameter.saturating_sub(1);
+
+        Modifiers {
+            shift: mask & 1 != 0,
+            alt: mask & 2 != 0,
+            ctrl: mask & 4 != 0,
+        }
+    }
+}
+
+/// A click or a wheel movement at a position of the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseKind,
+    pub position: Vector2,
+    pub modifiers: Modifiers,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseKind {
+    Press(MouseButton),
+    Release,
+    ScrollDown,
+    ScrollUp,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+enum Decoded {
+    /// The event and how many bytes it took
+    Event(InputEvent, usize),
+    /// Bytes that don't mean anything to us, e.g. unsupported sequences
+    Ignored(usize),
+    /// More bytes are needed to know, only when `more` might still arrive
+    Incomplete,
+}
+
+const ESCAPE: u8 = 0x1b;
+
+/// Decodes the first key in `bytes`, which must not be empty. Without `more` bytes coming,
+/// whatever is there is decoded as best as possible (e.g. a lone Escape).
+fn decode(bytes: &[u8], more: bool) -> Decoded {
+    match bytes[0] {
+        ESCAPE => match bytes.get(1) {
+            None if more => Decoded::Incomplete,
+            None => Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Escape)), 1),
+            Some(b'[') => match decode_csi(&bytes[2..]) {
+                Some(decoded) => offset(decoded, 2),
+                None if more => Decoded::Incomplete,
+                None => alt(decode(&bytes[1..], more), 1),
+            },
+            Some(b'O') => match bytes.get(2) {
+                Some(&byte) => match ss3_key(byte) {
+                    Some(key) => Decoded::Event(InputEvent::Key(KeyEvent::new(key)), 3),
+                    None => Decoded::Ignored(3),
+                },
+                None if more => Decoded::Incomplete,
+                None => alt(decode(&bytes[1..], more), 1),
+            },
+            // Alt is sent as an Escape before the key
+            Some(_) => alt(decode(&bytes[1..], more), 1),
+        },
+        b'\r' => Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Enter)), 1),
+        b'\t' => Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Tab)), 1),
+        0x08 | 0x7f => Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Backspace)), 1),
+        0x00 => Decoded::Event(InputEvent::Key(KeyEvent::ctrl(' ')), 1),
+        byte @ 0x01..=0x1a => Decoded::Event(
+            InputEvent::Key(KeyEvent::ctrl((b'a' + byte - 1) as char)),
+            1,
+        ),
+        byte @ 0x1c..=0x1f => {
+            Decoded::Event(InputEvent::Key(KeyEvent::ctrl((byte + 0x40) as char)), 1)
+        }
+        byte if byte.is_ascii() => {
+            Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Char(byte as char))), 1)
+        }
+        byte => {
+            let length = match byte {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => return Decoded::Ignored(1),
+            };
+
+            if bytes.len() < length {
+                return match more {
+                    true => Decoded::Incomplete,
+                    false => Decoded::Ignored(bytes.len()),
+                };
+            }
+
+            match std::str::from_utf8(&bytes[..length]).map(|text| text.chars().next()) {
+                Ok(Some(c)) => Decoded::Event(InputEvent::Key(KeyEvent::new(Key::Char(c))), length),
+                _ => Decoded::Ignored(1),
+            }
+        }
+    }
+}
+
+/// Decodes what comes after 'ESC [', `None` when the sequence isn't over yet.
+fn decode_csi(bytes: &[u8]) -> Option<Decoded> {
+    // Parameters and intermediate bytes come first, until the final byte
+    let final_position = bytes
+        .iter()
+        .position(|byte| !(0x20..=0x3f).contains(byte))?;
+    let final_byte = bytes[final_position];
+    let length = final_position + 1;
+
+    if !(0x40..=0x7e).contains(&final_byte) {
+        return Some(Decoded::Ignored(length));
+    }
+
+    if bytes[0] == b'<' {
+        return Some(decode_sgr_mouse(
+            &bytes[1..final_position],
+            final_byte,
+            length,
+        ));
+    }
+
+    let parameters: Vec<u16> = std::str::from_utf8(&bytes[..final_position])
+        .unwrap_or_default()
+        .split(';')
+        .map(|parameter| parameter.parse().unwrap_or(0))
+        .collect();
+    let modifiers = Modifiers::from_parameter(parameters.get(1).copied().unwrap_or(1));
+
+    let key = match final_byte {
+        b'~' => match parameters[0] {
+            1 | 7 => Key::Home,
+            2 => Key::Insert,
+            3 => Key::Delete,
+            4 | 8 => Key::End,
+            5 => Key::PageUp,
+            6 => Key::PageDown,
+            11..=15 => Key::F((parameters[0] - 10) as u8),
+            17..=21 => Key::F((parameters[0] - 11) as u8),
+            23 | 24 => Key::F((parameters[0] - 12) as u8),
+            _ => return Some(Decoded::Ignored(length)),
+        },
+        b'Z' => Key::BackTab,
+        byte => match ss3_key(byte) {
+            Some(key) => key,
+            None => return Some(Decoded::Ignored(length)),
+        },
+    };
+
+    Some(Decoded::Event(
+        InputEvent::Key(KeyEvent { key, modifiers }),
+        length,
+    ))
+}
+
+/// Decodes the 'button;x;y' parameters of a SGR mouse sequence, 'M' is a press and 'm' a release.
+fn decode_sgr_mouse(parameters: &[u8], final_byte: u8, length: usize) -> Decoded {
+    let parameters: Vec<usize> = std::str::from_utf8(parameters)
+        .unwrap_or_default()
+        .split(';')
+        .flat_map(|parameter| parameter.parse())
+        .collect();
+
+    let &[button, x, y] = parameters.as_slice() else {
+        return Decoded::Ignored(length);
+    };
+
+    let kind = match (final_byte, button & 0b1100_0011) {
+        (b'm', _) => MouseKind::Release,
+        (b'M', 0) => MouseKind::Press(MouseButton::Left),
+        (b'M', 1) => MouseKind::Press(MouseButton::Middle),
+        (b'M', 2) => MouseKind::Press(MouseButton::Right),
+        (b'M', 64) => MouseKind::ScrollUp,
+        (b'M', 65) => MouseKind::ScrollDown,
+        _ => return Decoded::Ignored(length),
+    };
+
+    let mouse = MouseEvent {
+        kind,
+        // The coordinates are 1-based
+        position: Vector2::new(x.saturating_sub(1), y.saturating_sub(1)),
+        modifiers: Modifiers {
+            shift: button & 4 != 0,
+            alt: button & 8 != 0,
+            ctrl: button & 16 != 0,
+        },
+    };
+
+    Decoded::Event(InputEvent::Mouse(mouse), length)
+}
+
+/// The final bytes shared between SS3 ('ESC O') and CSI sequences.
+fn ss3_key(byte: u8) -> Option<Key> {
+    match byte {
+        b'A' => Some(Key::Up),
+        b'B' => Some(Key::Down),
+        b'C' => Some(Key::Right),
+        b'D' => Some(Key::Left),
+        b'H' => Some(Key::Home),
+        b'F' => Some(Key::End),
+        b'P'..=b'S' => Some(Key::F(byte - b'P' + 1)),
+        _ => None,
+    }
+}
+
+fn offset(decoded: Decoded, bytes: usize) -> Decoded {
+    match decoded {
+        Decoded::Event(event, length) => Decoded::Event(event, length + bytes),
+        Decoded::Ignored(length) => Decoded::Ignored(length + bytes),
+        Decoded::Incomplete => Decoded::Incomplete,
+    }
+}
+
+fn alt(decoded: Decoded, bytes: usize) -> Decoded {
+    match offset(decoded, bytes) {
+        Decoded::Event(InputEvent::Key(mut key), length) => {
+            key.modifiers.alt = true;
+            Decoded::Event(InputEvent::Key(key), length)
+        }
+        decoded => decoded,
     }
 }
 
@@ -292,6 +896,7 @@ pub struct RenderingRegion {
     border_color: Option<Color>,
     vertical_alignment: VerticalAlignment,
     horizontal_alignment: HorizontalAlignment,
+    floating: bool,
 }
 
 impl RenderingRegion {
@@ -384,6 +989,44 @@ impl RenderingRegion {
         (top, bottom)
     }
 
+    /// Floating region
+    /// +------------+
+    /// |  +------+  |
+    /// |  |      |  |
+    /// |  +------+  |
+    /// +------------+
+    /// It's centered inside the current region and drawn over whatever is below it, so it must be
+    /// rendered after its neighbours.
+    pub fn floating(self, width: usize, height: usize) -> RenderingRegion {
+        let size = Size::new(
+            usize::min(width, self.size.width),
+            usize::min(height, self.size.height),
+        );
+        let position = self.position
+            + Vector2::new(
+                (self.size.width - size.width) / 2,
+                (self.size.height - size.height) / 2,
+            );
+
+        RenderingRegion {
+            position,
+            size,
+            floating: true,
+            ..Default::default()
+        }
+    }
+
+    fn overlay(&self, height: usize) -> RenderingRegion {
+        let height = usize::min(height, self.size.height);
+
+        RenderingRegion {
+            position: self.position + Vector2::new(0, self.size.height - height),
+            size: Size::new(self.size.width, height),
+            floating: true,
+            ..Default::default()
+        }
+    }
+
     pub fn text(self) -> Text {
         Text::new(self)
     }
@@ -396,6 +1039,10 @@ impl RenderingRegion {
         Table::new(self)
     }
 
+    pub fn input(self) -> Input {
+        Input::new(self)
+    }
+
     #[inline(always)]
     fn vertical_offset(&self, content_length: usize) -> usize {
         let border_offset = self.border_offset();
@@ -403,8 +1050,11 @@ impl RenderingRegion {
 
         match self.vertical_alignment {
             VerticalAlignment::Top => border_offset,
-            VerticalAlignment::Bottom => self.size.height - border_offset - content_length,
-            VerticalAlignment::Center => (self.size.height - content_length) / 2,
+            VerticalAlignment::Bottom => self
+                .size
+                .height
+                .saturating_sub(border_offset + content_length),
+            VerticalAlignment::Center => self.size.height.saturating_sub(content_length) / 2,
         }
     }
 
@@ -415,17 +1065,21 @@ impl RenderingRegion {
 
         match self.horizontal_alignment {
             HorizontalAlignment::Left => border_offset,
-            HorizontalAlignment::Right => self.size.width - border_offset - content_length,
-            HorizontalAlignment::Center => (self.size.width - content_length) / 2,
+            HorizontalAlignment::Right => self
+                .size
+                .width
+                .saturating_sub(border_offset + content_length),
+            HorizontalAlignment::Center => self.size.width.saturating_sub(content_length) / 2,
         }
     }
 
     #[inline(always)]
     pub fn usable_size(&self) -> Size {
         let border_offset = self.border_offset();
+        // Tiny terminals might not even fit the borders
         Size {
-            width: self.size.width - 2 * border_offset,
-            height: self.size.height - 2 * border_offset,
+            width: self.size.width.saturating_sub(2 * border_offset),
+            height: self.size.height.saturating_sub(2 * border_offset),
         }
     }
 
@@ -442,6 +1096,91 @@ impl RenderingRegion {
         buffer.cell_mut(self.position + position)
     }
 
+    /// Floating regions are drawn over other widgets, so we need to erase what is below them first.
+    fn clear(&self, buffer: &mut Buffer) {
+        if !self.floating {
+            return;
+        }
+
+        for y in 0..self.size.height {
+            for x in 0..self.size.width {
+                *self.cell_mut(buffer, Vector2::new(x, y)) = Cell::default();
+            }
+        }
+    }
+
+    fn contains(&self, position: Vector2) -> bool {
+        (self.position.x..self.position.x + self.size.width).contains(&position.x)
+            && (self.position.y..self.position.y + self.size.height).contains(&position.y)
+    }
+
+    /// Which of the `rows` laid out in the region is at `position`.
+    fn row_at(&self, position: Vector2, rows: usize) -> Option<usize> {
+        if !self.contains(position) {
+            return None;
+        }
+
+        let y = position.y - self.position.y;
+        let row = y.checked_sub(self.vertical_offset(rows))?;
+
+        (row < usize::min(rows, self.usable_size().height)).then_some(row)
+    }
+
+    /// Writes `footer` at the right end of the bottom border.
+    fn render_footer(&self, buffer: &mut Buffer, footer: &str) {
+        if self.border_color.is_none() || self.size.height == 0 {
+            return;
+        }
+
+        let length = unicode::width(footer);
+        let Some(start) = self.size.width.checked_sub(length + 2) else {
+            return;
+        };
+
+        let position = Vector2::new(start, self.size.height - 1);
+        self.write_str(buffer, position, footer, start + length);
+    }
+
+    /// Writes the `grapheme` at `position`, wide ones take the next cell as well.
+    fn write<'a>(
+        &self,
+        buffer: &'a mut Buffer,
+        position: Vector2,
+        grapheme: Grapheme,
+    ) -> &'a mut Cell {
+        if grapheme.width() == 2 {
+            let next = self.cell_mut(buffer, position + Vector2::new(1, 0));
+            next.grapheme = Grapheme::CONTINUATION;
+        }
+
+        let cell = self.cell_mut(buffer, position);
+        cell.grapheme = grapheme;
+
+        cell
+    }
+
+    /// Writes `text` from `position`, truncating it before the column `end`. Returns how many
+    /// columns it took.
+    fn write_str(&self, buffer: &mut Buffer, position: Vector2, text: &str, end: usize) -> usize {
+        let mut x = position.x;
+
+        for grapheme in unicode::graphemes(text).map(Grapheme::new) {
+            // Control characters or marks without anything to be drawn over
+            if grapheme.width() == 0 {
+                continue;
+            }
+
+            if x + grapheme.width() > end {
+                break;
+            }
+
+            self.write(buffer, Vector2::new(x, position.y), grapheme);
+            x += grapheme.width();
+        }
+
+        x - position.x
+    }
+
     fn highlight_row(&self, buffer: &mut Buffer, selected_row: usize) {
         for column in 0..self.size.width {
             let cell = self.cell_mut(buffer, Vector2::new(column, selected_row));
@@ -451,6 +1190,24 @@ impl RenderingRegion {
         }
     }
 
+    /// Unlike marks, the text keeps its weight.
+    fn color_row(&self, buffer: &mut Buffer, row: usize, color: Color) {
+        for column in 0..self.size.width {
+            self.cell_mut(buffer, Vector2::new(column, row))
+                .foreground_color = color;
+        }
+    }
+
+    /// The highlight of a selected row keeps the text bold.
+    fn mark_row(&self, buffer: &mut Buffer, marked_row: usize) {
+        for column in 0..self.size.width {
+            let cell = self.cell_mut(buffer, Vector2::new(column, marked_row));
+
+            cell.foreground_color = Color::Yellow;
+            cell.attributes.bold = true;
+        }
+    }
+
     fn render(&self, buffer: &mut Buffer) {
         if let Some(border_color) = self.border_color {
             for y in 0..self.size.height {
@@ -459,28 +1216,28 @@ impl RenderingRegion {
 
                     if y == 0 {
                         if x == 0 {
-                            cell.character = '┌';
+                            cell.grapheme = Grapheme::from('┌');
                             cell.foreground_color = border_color;
                         } else if x == self.size.width - 1 {
-                            cell.character = '┐';
+                            cell.grapheme = Grapheme::from('┐');
                             cell.foreground_color = border_color;
                         } else {
-                            cell.character = '─';
+                            cell.grapheme = Grapheme::from('─');
                             cell.foreground_color = border_color;
                         }
                     } else if y == self.size.height - 1 {
                         if x == 0 {
-                            cell.character = '└';
+                            cell.grapheme = Grapheme::from('└');
                             cell.foreground_color = border_color;
                         } else if x == self.size.width - 1 {
-                            cell.character = '┘';
+                            cell.grapheme = Grapheme::from('┘');
                             cell.foreground_color = border_color;
                         } else {
-                            cell.character = '─';
+                            cell.grapheme = Grapheme::from('─');
                             cell.foreground_color = border_color;
                         }
                     } else if x == 0 || x == self.size.width - 1 {
-                        cell.character = '│';
+                        cell.grapheme = Grapheme::from('│');
                         cell.foreground_color = border_color;
                         cell.background_color = Color::Black;
                     } else {
@@ -493,16 +1250,8 @@ impl RenderingRegion {
         let border_offset = self.border_offset();
 
         if let Some(title) = &self.title {
-            for (x, c) in title.chars().enumerate() {
-                let position = Vector2::new(x + 2, 0);
-
-                if position.x >= self.size.width - border_offset {
-                    break;
-                }
-
-                let cell = self.cell_mut(buffer, position);
-                cell.character = c
-            }
+            let end = self.size.width.saturating_sub(border_offset);
+            self.write_str(buffer, Vector2::new(2, 0), title, end);
         }
     }
 
@@ -531,19 +1280,79 @@ pub enum VerticalAlignment {
     Center,
 }
 
-#[derive(Copy, Clone)]
+/// What the user sees as a single character, e.g. a letter and its accent, or an emoji.
+/// It's kept inline so cells are cheap to copy around.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Grapheme {
+    bytes: [u8; Grapheme::CAPACITY],
+    length: u8,
+    width: u8,
+}
+
+impl Grapheme {
+    const CAPACITY: usize = 30;
+
+    /// The right half of a wide grapheme, which is never printed on its own
+    const CONTINUATION: Grapheme = Grapheme {
+        bytes: [0; Grapheme::CAPACITY],
+        length: 0,
+        width: 0,
+    };
+
+    fn new(grapheme: &str) -> Grapheme {
+        // Longer sequences (e.g. some joined emoji) only keep their first character
+        let grapheme = match grapheme.len() <= Grapheme::CAPACITY {
+            true => grapheme,
+            false => grapheme
+                .char_indices()
+                .nth(1)
+                .map_or(grapheme, |(end, _)| &grapheme[..end]),
+        };
+
+        let mut bytes = [0; Grapheme::CAPACITY];
+        bytes[..grapheme.len()].copy_from_slice(grapheme.as_bytes());
+
+        Grapheme {
+            bytes,
+            length: grapheme.len() as u8,
+            width: unicode::grapheme_width(grapheme) as u8,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.length as usize]).unwrap_or_default()
+    }
+
+    fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    fn is_continuation(&self) -> bool {
+        self.length == 0
+    }
+}
+
+impl From<char> for Grapheme {
+    fn from(c: char) -> Self {
+        Grapheme::new(c.encode_utf8(&mut [0; 4]))
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct Cell {
-    character: char,
+    grapheme: Grapheme,
     foreground_color: Color,
     background_color: Color,
+    attributes: Attributes,
 }
 
 impl Default for Cell {
     fn default() -> Self {
         Cell {
-            character: ' ',
+            grapheme: Grapheme::from(' '),
             foreground_color: Color::Default,
             background_color: Color::Default,
+            attributes: Attributes::default(),
         }
     }
 }
@@ -552,76 +1361,174 @@ impl Default for Cell {
 pub enum Color {
     // User's terminal default color
     Black,
+    Blue,
     Cyan,
     Default,
     Green,
+    Yellow,
 }
 
 impl Color {
-    fn apply_foreground(&self) {
+    fn apply_foreground(&self, output: &mut String) {
         match self {
-            Color::Black => print!("\x1b[30m"),
-            Color::Cyan => print!("\x1b[36m"),
-            Color::Default => print!("\x1b[39m"),
-            Color::Green => print!("\x1b[32m"),
+            Color::Black => output.push_str("\x1b[30m"),
+            Color::Blue => output.push_str("\x1b[34m"),
+            Color::Cyan => output.push_str("\x1b[36m"),
+            Color::Default => output.push_str("\x1b[39m"),
+            Color::Green => output.push_str("\x1b[32m"),
+            Color::Yellow => output.push_str("\x1b[33m"),
         }
     }
 
-    fn apply_background(&self) {
+    fn apply_background(&self, output: &mut String) {
         match self {
-            Color::Black => print!("\x1b[40m"),
-            Color::Cyan => print!("\x1b[46m"),
-            Color::Default => print!("\x1b[49m"),
-            Color::Green => print!("\x1b[42m"),
+            Color::Black => output.push_str("\x1b[40m"),
+            Color::Blue => output.push_str("\x1b[44m"),
+            Color::Cyan => output.push_str("\x1b[46m"),
+            Color::Default => output.push_str("\x1b[49m"),
+            Color::Green => output.push_str("\x1b[42m"),
+            Color::Yellow => output.push_str("\x1b[43m"),
         }
     }
 }
 
-struct HardwrappingText<'a> {
-    text: &'a [char],
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Attributes {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+}
+
+impl Attributes {
+    fn apply(&self, output: &mut String) {
+        // We reset every attribute and then enable the ones we want
+        output.push_str("\x1b[22;23;24;29m");
+
+        if self.bold {
+            output.push_str("\x1b[1m");
+        }
+        if self.italic {
+            output.push_str("\x1b[3m");
+        }
+        if self.underline {
+            output.push_str("\x1b[4m");
+        }
+        if self.strikethrough {
+            output.push_str("\x1b[9m");
+        }
+    }
+}
+
+/// How a piece of text looks, without a foreground color it keeps the one from the widget.
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Style {
+    pub foreground_color: Option<Color>,
+    pub attributes: Attributes,
+}
+
+/// A piece of text that shares the same style.
+#[derive(Clone)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    pub fn new(text: impl Into<String>, style: Style) -> Span {
+        Span {
+            text: text.into(),
+            style,
+        }
+    }
+}
+
+/// Splits the text in lines no wider than `width` columns, breaking them between words when
+/// possible.
+struct WrappingText<'a> {
+    text: &'a [Grapheme],
     width: usize,
+    /// Where the remaining text starts in the original one
+    position: usize,
 }
 
-impl<'a> HardwrappingText<'a> {
-    pub fn new(text: &'a [char], width: usize) -> Self {
-        Self { text, width }
+impl<'a> WrappingText<'a> {
+    pub fn new(text: &'a [Grapheme], width: usize) -> Self {
+        Self {
+            text,
+            width,
+            position: 0,
+        }
     }
 }
 
-impl<'a> Iterator for HardwrappingText<'a> {
-    type Item = &'a [char];
+impl<'a> Iterator for WrappingText<'a> {
+    /// The line and where it starts in the original text
+    type Item = (usize, &'a [Grapheme]);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.text.is_empty() {
+        // Nothing fits in a zero width, we would never move forward
+        if self.text.is_empty() || self.width == 0 {
             return None;
         }
 
-        let mut found_newline = false;
-        let line_end = match self.text.iter().position(|c| c == &'\n') {
-            Some(position) => {
-                found_newline = true;
-                position
+        let line_end = self
+            .text
+            .iter()
+            .position(|grapheme| grapheme.as_str() == "\n")
+            .unwrap_or(self.text.len());
+
+        // How many graphemes of the line fit in the width
+        let mut line_width = 0;
+        let fitting = self.text[..line_end]
+            .iter()
+            .take_while(|grapheme| {
+                line_width += grapheme.width();
+                line_width <= self.width
+            })
+            .count();
+
+        // We do not want to print the '\n' or the space where the line is broken, but we do
+        // want to remove them from the buffer so we can parse the next line later
+        let (wrapped_line_end, consumed) = if fitting == line_end {
+            (line_end, usize::min(line_end + 1, self.text.len()))
+        } else {
+            match self.text[..=fitting]
+                .iter()
+                .rposition(|grapheme| grapheme.as_str() == " ")
+            {
+                Some(space) if space > 0 => (space, space + 1),
+                // A single word wider than the line has to be cut, even if nothing fits
+                _ => (usize::max(fitting, 1), usize::max(fitting, 1)),
             }
-            None => self.text.len(),
         };
 
-        // FIXME: Account for word boundaries
-
-        // We do not want to print the '\n' but we do want to remove it from the buffer so we can
-        // parse the next line later, otherwise it gets stuck
-        let strip_newline = found_newline & (line_end <= self.width);
-        let hardwrapped_line_end = usize::min(self.width, line_end);
-
-        let result = &self.text[0..hardwrapped_line_end];
-        self.text = &self.text[hardwrapped_line_end + strip_newline as usize..];
+        let result = (self.position, &self.text[0..wrapped_line_end]);
+        self.text = &self.text[consumed..];
+        self.position += consumed;
 
         Some(result)
     }
 }
 
+/// How far the text of a `Text` widget is moved.
+#[derive(Clone, Copy)]
+pub enum Scroll {
+    LineDown,
+    LineUp,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+}
+
 #[derive(Default)]
 pub struct Text {
-    text: Vec<char>,
+    text: Vec<Grapheme>,
+    /// The style of every grapheme in `text`
+    styles: Vec<Style>,
+    /// How many lines are scrolled past
+    scroll: usize,
     rendering_region: RenderingRegion,
 }
 
@@ -636,46 +1543,123 @@ impl Text {
     }
 
     pub fn set_text(&mut self, new_text: Option<String>) {
-        if let Some(text) = new_text {
+        let spans = new_text
+            .map(|text| vec![Span::new(text, Style::default())])
+            .unwrap_or_default();
+
+        self.set_spans(spans);
+    }
+
+    /// The scroll is kept unless the text changes, as the same text is set again on every sync.
+    pub fn set_spans(&mut self, spans: Vec<Span>) {
+        let mut text = Vec::new();
+        let mut styles = Vec::new();
+
+        for span in spans {
             // If not removed the tabs will be rendered as multiple spaces but the renderer will
             // count only one character, breaking the UI
-            let text = text.replace('\t', "    ");
-            // Some unicode characters are not rendered, breaking the UI
-            // TODO: Find a scalable way to keep only "printable" characters
-            self.text = text.chars().filter(|c| *c != '\u{300}').collect();
-        } else {
-            self.text.clear();
+            let span_text = span.text.replace('\t', "    ");
+
+            // Split first, a mark right after a line break would otherwise take it with it
+            for (index, line) in span_text.split('\n').enumerate() {
+                if index > 0 {
+                    text.push(Grapheme::from('\n'));
+                    styles.push(span.style);
+                }
+
+                for grapheme in unicode::graphemes(line).map(Grapheme::new) {
+                    // Control characters (e.g. '\r') would mess with the terminal
+                    if grapheme.width() == 0 {
+                        continue;
+                    }
+
+                    text.push(grapheme);
+                    styles.push(span.style);
+                }
+            }
+        }
+
+        if text != self.text {
+            self.scroll = 0;
         }
+
+        self.text = text;
+        self.styles = styles;
+    }
+
+    pub fn scroll(&mut self, scroll: Scroll) {
+        let height = self.rendering_region.usable_size().height;
+        let half_page = usize::max(height / 2, 1);
+
+        self.scroll = match scroll {
+            Scroll::LineDown => self.scroll + 1,
+            Scroll::LineUp => self.scroll.saturating_sub(1),
+            Scroll::HalfPageDown => self.scroll + half_page,
+            Scroll::HalfPageUp => self.scroll.saturating_sub(half_page),
+            Scroll::Top => 0,
+            Scroll::Bottom => usize::MAX,
+        };
+
+        self.scroll = usize::min(self.scroll, self.max_scroll());
+    }
+
+    fn lines(&self) -> WrappingText<'_> {
+        WrappingText::new(&self.text, self.rendering_region.usable_size().width)
+    }
+
+    /// The last line is never scrolled past the bottom of the widget.
+    fn max_scroll(&self) -> usize {
+        self.lines()
+            .count()
+            .saturating_sub(self.rendering_region.usable_size().height)
     }
 }
 
 impl Widget for Text {
     fn render(&self, buffer: &mut Buffer) {
-        let lines_count =
-            HardwrappingText::new(&self.text, self.rendering_region.usable_size().width).count();
+        self.rendering_region.clear(buffer);
+
+        let height = self.rendering_region.usable_size().height;
+        let lines_count = self.lines().count();
+        let scroll = usize::min(self.scroll, self.max_scroll());
 
         let y_offset = self.rendering_region.vertical_offset(lines_count);
 
-        for (line_index, line) in
-            HardwrappingText::new(&self.text, self.rendering_region.usable_size().width)
-                .take(self.rendering_region.usable_size().height)
-                .enumerate()
-        {
-            let line_length = line.len();
+        for (line_index, (line_start, line)) in self.lines().skip(scroll).take(height).enumerate() {
+            let line_width = line.iter().map(Grapheme::width).sum();
 
-            let x_offset = self.rendering_region.horizontal_offset(line_length);
+            let mut x = self.rendering_region.horizontal_offset(line_width);
+            let end = x + self.rendering_region.usable_size().width;
 
-            for (row_index, c) in line.iter().enumerate() {
-                let cell = self.rendering_region.cell_mut(
+            for (index, grapheme) in line.iter().enumerate() {
+                // A wide grapheme might not fit even on a line of its own
+                if x + grapheme.width() > end {
+                    break;
+                }
+
+                let cell = self.rendering_region.write(
                     buffer,
-                    Vector2::new(row_index + x_offset, line_index + y_offset),
+                    Vector2::new(x, line_index + y_offset),
+                    *grapheme,
                 );
+                x += grapheme.width();
+
+                let style = self.styles[line_start + index];
 
-                cell.character = *c;
+                cell.attributes = style.attributes;
+                if let Some(color) = style.foreground_color {
+                    cell.foreground_color = color;
+                }
             }
         }
 
         self.rendering_region.render(buffer);
+
+        // Only text that doesn't fit tells where we are in it
+        if lines_count > height {
+            self.rendering_region
+                .render_footer(buffer, &format!(" line {}/{lines_count} ", scroll + 1));
+        }
     }
 }
 
@@ -704,7 +1688,6 @@ impl ItemList {
         let inner_size = self.rendering_region.usable_size();
 
         assert!(items.len() <= inner_size.height);
-        assert!(items.iter().map(|item| item.len()).max() < Some(inner_size.width));
 
         self.items = items;
         self.selected_row = None;
@@ -713,28 +1696,41 @@ impl ItemList {
     pub fn set_selected(&mut self, item_index: Option<usize>) {
         self.selected_row = item_index
     }
+
+    /// The item shown at `position` of the screen, if any.
+    pub fn row_at(&self, position: Vector2) -> Option<usize> {
+        self.rendering_region.row_at(position, self.items.len())
+    }
 }
 
 impl Widget for ItemList {
     fn render(&self, buffer: &mut Buffer) {
+        self.rendering_region.clear(buffer);
+
         let y_offset = self.rendering_region.vertical_offset(self.items.len());
-        let x_offset = self
-            .rendering_region
-            .horizontal_offset(self.items.iter().map(|item| item.len()).max().unwrap_or(0));
+        let x_offset = self.rendering_region.horizontal_offset(
+            self.items
+                .iter()
+                .map(|item| unicode::width(item))
+                .max()
+                .unwrap_or(0),
+        );
 
         if let Some(selected_row) = self.selected_row {
             self.rendering_region
                 .highlight_row(buffer, y_offset + selected_row)
         }
 
-        for (y, item) in self.items.iter().enumerate() {
-            for (x, c) in item.chars().enumerate() {
-                let cell = self
-                    .rendering_region
-                    .cell_mut(buffer, Vector2::new(x + x_offset, y + y_offset));
+        let usable_width = self.rendering_region.usable_size().width;
 
-                cell.character = c;
-            }
+        for (y, item) in self.items.iter().enumerate() {
+            // Items wider than the widget are truncated to avoid leaving the rendering area
+            self.rendering_region.write_str(
+                buffer,
+                Vector2::new(x_offset, y + y_offset),
+                item,
+                x_offset + usable_width,
+            );
         }
 
         self.rendering_region.render(buffer);
@@ -746,6 +1742,8 @@ pub struct Table {
     items: Vec<Vec<String>>,
     rendering_region: RenderingRegion,
     selected_row: Option<usize>,
+    marked_rows: Vec<usize>,
+    changed_rows: Vec<usize>,
 }
 
 implement_common_widget!(Table);
@@ -762,63 +1760,288 @@ impl Table {
         self.selected_row = row_index
     }
 
+    /// The row shown at `position` of the screen, if any.
+    pub fn row_at(&self, position: Vector2) -> Option<usize> {
+        self.rendering_region.row_at(position, self.items.len())
+    }
+
+    /// Marked rows stand out even when they are selected.
+    pub fn set_marked(&mut self, row_indexes: Vec<usize>) {
+        self.marked_rows = row_indexes;
+    }
+
+    /// Changed rows are colored under the marks and the selection.
+    pub fn set_changed(&mut self, row_indexes: Vec<usize>) {
+        self.changed_rows = row_indexes;
+    }
+
     pub fn change_table(&mut self, items: Vec<Vec<String>>) {
         self.items = items;
         self.selected_row = None;
+        self.marked_rows.clear();
+        self.changed_rows.clear();
     }
 }
 
 impl Widget for Table {
     fn render(&self, buffer: &mut Buffer) {
+        self.rendering_region.clear(buffer);
+
         let usable_size = self.rendering_region.usable_size();
 
-        let max_row_size = self.items.iter().map(|row| row.len()).max().unwrap();
+        let max_row_size = self.items.iter().map(|row| row.len()).max().unwrap_or(0);
 
         let mut column_lengths = vec![0; max_row_size];
         for row in self.items.iter() {
             for (i, item) in row.iter().enumerate() {
-                if item.len() > column_lengths[i] {
-                    column_lengths[i] = item.len();
-                }
+                column_lengths[i] = usize::max(column_lengths[i], unicode::width(item));
             }
         }
 
         let y_offset = self.rendering_region.vertical_offset(self.items.len());
 
+        for changed_row in &self.changed_rows {
+            self.rendering_region
+                .color_row(buffer, y_offset + changed_row, Color::Green);
+        }
+
+        for marked_row in &self.marked_rows {
+            self.rendering_region
+                .mark_row(buffer, y_offset + marked_row);
+        }
+
         if let Some(selected_row) = self.selected_row {
             self.rendering_region
                 .highlight_row(buffer, y_offset + selected_row)
         }
 
         for (row_index, row) in self.items.iter().enumerate() {
-            'line: for (column_index, item) in row.iter().enumerate() {
-                let column_offset = column_lengths.iter().take(column_index).sum::<usize>();
-                let x_offset = self.rendering_region.horizontal_offset(item.len());
+            for (column_index, item) in row.iter().enumerate() {
+                // We sum the 'column_index' to add gaps
+                let x = column_index + column_lengths.iter().take(column_index).sum::<usize>();
 
-                for (k, c) in item.chars().enumerate() {
-                    // We sum the 'column_index' to add gaps
-                    let x = column_index + k + column_offset;
+                // This truncates the line to avoid leaving the rendering area
+                if x >= usable_size.width {
+                    break;
+                }
 
-                    // This truncates the line to avoid leaving the rendering area
-                    if x >= usable_size.width {
-                        break 'line;
-                    }
+                let x_offset = self
+                    .rendering_region
+                    .horizontal_offset(unicode::width(item));
+                self.rendering_region.write_str(
+                    buffer,
+                    Vector2::new(x + x_offset, row_index + y_offset),
+                    item,
+                    usable_size.width + x_offset,
+                );
+            }
+        }
+        self.rendering_region.render(buffer);
+    }
+}
 
-                    let cell = self
-                        .rendering_region
-                        .cell_mut(buffer, Vector2::new(x + x_offset, row_index + y_offset));
+/// Single line text input, when the text is wider than the widget only its end is shown.
+#[derive(Default)]
+pub struct Input {
+    text: String,
+    rendering_region: RenderingRegion,
+}
 
-                    cell.character = c;
-                }
-            }
+implement_common_widget!(Input);
+
+impl Input {
+    pub fn new(rendering_region: RenderingRegion) -> Input {
+        Input {
+            rendering_region,
+            ..Default::default()
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    /// Removes the last grapheme, along with any accent it has.
+    pub fn pop(&mut self) {
+        if let Some(last) = unicode::graphemes(&self.text).last() {
+            self.text.truncate(self.text.len() - last.len());
         }
+    }
+}
+
+impl Widget for Input {
+    fn render(&self, buffer: &mut Buffer) {
+        self.rendering_region.clear(buffer);
+
+        let usable_size = self.rendering_region.usable_size();
+        let y_offset = self.rendering_region.vertical_offset(1);
+        let x_offset = self.rendering_region.border_offset();
+
+        // We keep the last column free for the cursor
+        let available_width = usable_size.width.saturating_sub(1);
+
+        // Only the end of the text that fits is shown
+        let mut visible_width = 0;
+        let visible_start = unicode::graphemes(&self.text)
+            .collect::<Vec<_>>()
+            .iter()
+            .rev()
+            .take_while(|grapheme| {
+                visible_width += unicode::grapheme_width(grapheme);
+                visible_width <= available_width
+            })
+            .map(|grapheme| grapheme.len())
+            .sum::<usize>();
+        let visible_text = &self.text[self.text.len() - visible_start..];
+
+        let visible_width = self.rendering_region.write_str(
+            buffer,
+            Vector2::new(x_offset, y_offset),
+            visible_text,
+            x_offset + available_width,
+        );
+
+        let cursor = self
+            .rendering_region
+            .cell_mut(buffer, Vector2::new(visible_width + x_offset, y_offset));
+        cursor.background_color = Color::Cyan;
+
         self.rendering_region.render(buffer);
     }
 }
 
-// TODO: Add diff-rendering instead of clearing and rendering everything back again on every tick
-// TODO: Add floating panel
 // TODO: Can we get away with '&str' instead of 'String' everywhere in the Tui?
-// TODO: Handle resizes
 // TODO: Add tests with expectations
 // TODO: Add manual libc binding
+
+#[cfg(test)]
+mod test {
+    use super::{
+        Decoded, Grapheme, InputEvent, Key, KeyEvent, Modifiers, MouseButton, MouseEvent,
+        MouseKind, Vector2, WrappingText, decode,
+    };
+
+    fn decode_all(mut bytes: &[u8]) -> Vec<KeyEvent> {
+        let mut keys = Vec::new();
+
+        while !bytes.is_empty() {
+            match decode(bytes, false) {
+                Decoded::Event(InputEvent::Key(key), length) => {
+                    keys.push(key);
+                    bytes = &bytes[length..];
+                }
+                Decoded::Event(InputEvent::Mouse(_), length) => bytes = &bytes[length..],
+                Decoded::Ignored(length) => bytes = &bytes[length..],
+                Decoded::Incomplete => unreachable!(),
+            }
+        }
+
+        keys
+    }
+
+    #[test]
+    fn decode_sequences() {
+        let ctrl_up = KeyEvent {
+            key: Key::Up,
+            modifiers: Modifiers {
+                ctrl: true,
+                ..Modifiers::default()
+            },
+        };
+
+        assert_eq!(
+            decode_all(b"\x1b[A\x1bOP\x1b[5~\x1b[1;5A\x1b[24~"),
+            vec![
+                KeyEvent::new(Key::Up),
+                KeyEvent::new(Key::F(1)),
+                KeyEvent::new(Key::PageUp),
+                ctrl_up,
+                KeyEvent::new(Key::F(12)),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_escape_and_alt() {
+        let alt_j = KeyEvent {
+            key: Key::Char('j'),
+            modifiers: Modifiers {
+                alt: true,
+                ..Modifiers::default()
+            },
+        };
+
+        assert!(matches!(decode(b"\x1b", true), Decoded::Incomplete));
+        assert!(matches!(decode(b"\x1b[1;", true), Decoded::Incomplete));
+        assert_eq!(decode_all(b"\x1b"), vec![KeyEvent::new(Key::Escape)]);
+        assert_eq!(decode_all(b"\x1bj"), vec![alt_j]);
+        assert_eq!(decode_all(b"\x03"), vec![KeyEvent::ctrl('c')]);
+    }
+
+    #[test]
+    fn decode_utf8() {
+        assert!(matches!(
+            decode("é".as_bytes()[..1].as_ref(), true),
+            Decoded::Incomplete
+        ));
+        assert_eq!(
+            decode_all("aé→".as_bytes()),
+            vec![
+                KeyEvent::new(Key::Char('a')),
+                KeyEvent::new(Key::Char('é')),
+                KeyEvent::new(Key::Char('→')),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_mouse() {
+        let Decoded::Event(InputEvent::Mouse(mouse), 10) = decode(b"\x1b[<0;12;5M", false) else {
+            panic!("Expected a mouse event");
+        };
+
+        assert_eq!(
+            mouse,
+            MouseEvent {
+                kind: MouseKind::Press(MouseButton::Left),
+                position: Vector2::new(11, 4),
+                modifiers: Modifiers::default(),
+            }
+        );
+
+        let Decoded::Event(InputEvent::Mouse(mouse), _) = decode(b"\x1b[<65;1;1M", false) else {
+            panic!("Expected a mouse event");
+        };
+
+        assert_eq!(mouse.kind, MouseKind::ScrollDown);
+        assert!(decode_all(b"\x1b[<0;12;5m").is_empty());
+    }
+
+    #[test]
+    fn wrap_between_words() {
+        let text: Vec<Grapheme> = "The login fails\nwith an unexpected error"
+            .chars()
+            .map(Grapheme::from)
+            .collect();
+
+        let lines: Vec<String> = WrappingText::new(&text, 10)
+            .map(|(_, line)| line.iter().map(Grapheme::as_str).collect())
+            .collect();
+
+        assert_eq!(
+            lines,
+            ["The login", "fails", "with an", "unexpected", "error"]
+        );
+
+        let text: Vec<Grapheme> = "Supercalifragilistic".chars().map(Grapheme::from).collect();
+        assert_eq!(WrappingText::new(&text, 8).count(), 3);
+
+        // Wide graphemes take two columns
+        let text: Vec<Grapheme> = "日本語の説明".chars().map(Grapheme::from).collect();
+        assert_eq!(WrappingText::new(&text, 5).count(), 3);
+    }
+}